@@ -1,5 +1,5 @@
 cd ..
 
-near deploy --wasmFile res/kulapad_ido.wasm --accountId ido-kulapad.testnet --initFunction migrate --initArgs '{"owner_id": "ido-kulapad.testnet"}'
+near deploy --wasmFile res/kulapad_ido.wasm --accountId ido-kulapad.testnet --initFunction migrate --initArgs '{}'
 
 read -p "Press any key to continue..."
\ No newline at end of file
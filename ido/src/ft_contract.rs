@@ -1,20 +1,58 @@
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds};
 use near_sdk::Gas;
+use near_sdk::json_types::ValidAccountId;
 
 use crate::*;
 
 pub const FT_TRANSFER_GAS: Gas = 10_000_000_000_000;
+pub const FT_TRANSFER_CALL_GAS: Gas = 60_000_000_000_000;
 pub const TRANSFER_CALLBACK_GAS: Gas = 10_000_000_000_000;
+pub const FT_METADATA_GAS: Gas = 10_000_000_000_000;
+pub const FT_METADATA_CALLBACK_GAS: Gas = 10_000_000_000_000;
+pub const FT_STORAGE_DEPOSIT_GAS: Gas = 10_000_000_000_000;
+pub const FT_STORAGE_BALANCE_OF_GAS: Gas = 5_000_000_000_000;
+pub const FT_STORAGE_BALANCE_BOUNDS_GAS: Gas = 5_000_000_000_000;
+/// The callback registers the receiver if needed, then schedules the transfer and its callback.
+pub const CLAIM_TO_CALLBACK_GAS: Gas = 40_000_000_000_000;
+
+/// The usual storage deposit that registers an account on a NEP-141 token (0.00125 NEAR).
+/// It's the least deposit of a claim to another account, the real one is read from storage_balance_bounds of the token.
+pub const FT_STORAGE_DEPOSIT: Balance = 1_250_000_000_000_000_000_000;
 
 pub const DEPOSIT_ONE_YOCTOR: Balance = 1;
 
+/// The maximum number of projects in a claim_all call, every sale token needs a transfer and a callback.
+pub const MAX_CLAIM_ALL_PROJECTS: usize = 10;
+
 #[ext_contract(ext_ft_contract)]
 pub trait FungibleTokenCore {
     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+    fn ft_transfer_call(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>, msg: String) -> PromiseOrValue<U128>;
+}
+
+#[ext_contract(ext_ft_storage)]
+pub trait FungibleTokenStorage {
+    fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>);
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance>;
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds;
+}
+
+#[ext_contract(ext_ft_metadata)]
+pub trait FungibleTokenMetadataProvider {
+    fn ft_metadata(&self) -> FungibleTokenMetadata;
 }
 
 #[ext_contract(ext_self)]
 pub trait ExtStakingContract {
-    fn ft_transfer_callback(&mut self, project_id: ProjectId, account_id: AccountId, claim_amount: U128);
+    fn ft_transfer_callback(&mut self, account_id: AccountId, claims: Vec<(ProjectId, U128)>) -> U128;
+    fn claim_to_callback(&mut self, project_id: ProjectId, account_id: AccountId, receiver_id: AccountId, payer_id: AccountId) -> Promise;
+    fn claim_and_stake_callback(&mut self, account_id: AccountId, claims: Vec<(ProjectId, U128)>, claim_amount: U128) -> U128;
+    fn refund_callback(&mut self, project_id: ProjectId, account_id: AccountId, refund_amount: U128);
+    fn unsold_tokens_callback(&mut self, project_id: ProjectId) -> U128;
+    fn withdraw_fund_callback(&mut self, project_id: ProjectId, withdraw_amount: U128) -> U128;
+    fn resolve_ft_metadata(&mut self, project_id: ProjectId, is_fund_token: bool) -> Option<u8>;
+    fn nft_resolve_transfer(&mut self, previous_owner_id: AccountId, receiver_id: AccountId, token_id: VestingTokenId, approved_account_ids: Option<HashMap<AccountId, u64>>) -> bool;
 }
 
 // Firstly, user must call ft_transfer_call function from ft contract.
@@ -32,77 +70,617 @@ pub trait IDOContractResolver{
 #[near_bindgen]
 impl IDOContract {
     /// User can claim their bought unlocked token after sales.
+    /// The token of all distributed rounds of the project is claimed together.
     #[payable]
     pub fn claim(&mut self, project_id: ProjectId) -> Promise {
         let account_id: AccountId = env::predecessor_account_id();
+        self.internal_claim_to(project_id, account_id.clone(), account_id)
+    }
+
+    /// Claim the token of the caller to another account, ex: a cold wallet or a DAO.
+    /// The attached deposit registers the receiver on the sale token, it's returned to the caller if the receiver is already registered.
+    #[payable]
+    pub fn claim_to(&mut self, project_id: ProjectId, receiver_id: ValidAccountId) -> Promise {
+        let account_id: AccountId = env::predecessor_account_id();
+        self.internal_claim_to(project_id, account_id, receiver_id.into())
+    }
+
+    /// A claim delegate claims the token of an account that authorized it.
+    /// The token is transferred to the receiver, or the delegate if the receiver is not set.
+    #[payable]
+    pub fn claim_for(&mut self, project_id: ProjectId, account_id: ValidAccountId, receiver_id: Option<ValidAccountId>) -> Promise {
+        let delegate_id: AccountId = env::predecessor_account_id();
+        let account_id: AccountId = account_id.into();
+        self.assert_claim_delegate(&account_id, &delegate_id);
+
+        let receiver_id: AccountId = receiver_id.map_or(delegate_id, |receiver_id| receiver_id.into());
+        self.internal_claim_to(project_id, account_id, receiver_id)
+    }
+
+    /// Claim the token of many projects in one transaction, the claims are grouped by the sale token.
+    /// All claims are recorded before the transfers, and every transfer has its own callback,
+    /// so a failed transfer only rolls back the claims of its token.
+    /// The projects that are not claimable are skipped.
+    #[payable]
+    pub fn claim_all(&mut self, project_ids: Vec<ProjectId>) -> Promise {
+        assert_one_yocto();
+        assert!(!self.paused, "The contract is paused.");
+        assert!(project_ids.len() <= MAX_CLAIM_ALL_PROJECTS, "Can't claim more than {} projects at once.", MAX_CLAIM_ALL_PROJECTS);
+
+        let account_id: AccountId = env::predecessor_account_id();
+        let claims_by_token = self.internal_get_claims_by_token(&account_id, project_ids);
+        assert!(!claims_by_token.is_empty(), "The account has nothing to claim.");
+
+        for (_, claims) in claims_by_token.iter() {
+            self.internal_record_claims(&account_id, claims);
+        }
+
+        claims_by_token.into_iter()
+            .map(|(token_contract_id, claims)| self.internal_transfer_claims(token_contract_id, account_id.clone(), account_id.clone(), claims, None))
+            .reduce(|promise, next_promise| promise.and(next_promise))
+            .unwrap()
+    }
+
+    /// The claimable token of the projects and their rounds grouped by the sale token.
+    /// A project is claimed once even if it's listed many times or together with its rounds.
+    /// The projects that the account can't claim because of the blacklist are skipped.
+    pub(crate) fn internal_get_claims_by_token(&self, account_id: &AccountId, project_ids: Vec<ProjectId>) -> Vec<(AccountId, Vec<(ProjectId, U128)>)> {
+        let mut main_project_ids: Vec<ProjectId> = vec![];
+        for project_id in project_ids {
+            let project = self.internal_get_project_or_panic(project_id);
+            let main_project_id = project.parent_id.unwrap_or(project_id);
+            if !main_project_ids.contains(&main_project_id) {
+                main_project_ids.push(main_project_id);
+            }
+        }
+
+        let mut claims_by_token: Vec<(AccountId, Vec<(ProjectId, U128)>)> = vec![];
+        for main_project_id in main_project_ids {
+            if !self.can_claim(main_project_id, account_id) {
+                continue;
+            }
+
+            let claims = self.internal_claim_rounds(main_project_id, account_id);
+            if claims.is_empty() {
+                continue;
+            }
+
+            let token_contract_id = self.internal_get_project_or_panic(main_project_id).token_contract_id;
+            match claims_by_token.iter_mut().find(|(id, _)| *id == token_contract_id) {
+                Some((_, token_claims)) => token_claims.extend(claims),
+                None => claims_by_token.push((token_contract_id, claims)),
+            }
+        }
+
+        claims_by_token
+    }
+
+    /// Transfer the claimable token of the account to the receiver.
+    /// The claims are recorded before the transfer and rolled back by the callback if the transfer fails.
+    /// A receiver other than the account is registered on the sale token first, the caller attaches its storage deposit.
+    /// The claims to another receiver are recorded by claim_to_callback, so nothing is recorded if the callback fails.
+    pub(crate) fn internal_claim_to(&mut self, project_id: ProjectId, account_id: AccountId, receiver_id: AccountId) -> Promise {
         let project = self.internal_get_project_or_panic(project_id);
         assert!(project.is_in_distribution_period(), "The project isn't in distribution period.");
+        self.assert_not_paused(&project);
+        self.assert_can_claim(project_id, &account_id);
+        self.assert_can_claim(project_id, &receiver_id);
+
+        if receiver_id == account_id {
+            assert_one_yocto();
+        } else {
+            assert!(env::attached_deposit() >= FT_STORAGE_DEPOSIT,
+                "Requires attached deposit of at least {} yoctoNEAR for the storage registration of the receiver.", FT_STORAGE_DEPOSIT);
+        }
+
+        let claims = self.internal_claim_rounds(project_id, &account_id);
+        let claim_amount: Balance = claims.iter().map(|(_, amount)| amount.0).sum();
+        assert!(claim_amount > 0, "The account has nothing to claim.");
+
+        if receiver_id == account_id {
+            self.internal_record_claims(&account_id, &claims);
+            env::log(format!("Claim: account_id={}, receiver_id={}, amount={}", account_id, receiver_id, claim_amount).as_bytes());
+
+            return self.internal_transfer_claims(project.token_contract_id, account_id.clone(), account_id, claims, None);
+        }
+
+        ext_ft_storage::storage_balance_of(
+            receiver_id.clone(),
+            &project.token_contract_id,
+            NO_DEPOSIT,
+            FT_STORAGE_BALANCE_OF_GAS
+        ).and(
+            ext_ft_storage::storage_balance_bounds(
+                &project.token_contract_id,
+                NO_DEPOSIT,
+                FT_STORAGE_BALANCE_BOUNDS_GAS
+            )
+        ).then(
+            ext_self::claim_to_callback(
+                project_id,
+                account_id,
+                receiver_id,
+                env::predecessor_account_id(),
+                &env::current_account_id(),
+                env::attached_deposit(),
+                CLAIM_TO_CALLBACK_GAS
+            )
+        )
+    }
+
+    /// The storage deposit is attached to this callback. The minimum storage deposit of the sale token registers the receiver,
+    /// the rest is returned to the payer. The whole deposit is returned if the receiver is already registered.
+    /// The claims are recorded here, the deposit is returned and nothing is claimed if it doesn't cover the registration.
+    #[private]
+    #[payable]
+    pub fn claim_to_callback(&mut self, project_id: ProjectId, account_id: AccountId, receiver_id: AccountId, payer_id: AccountId) -> Promise {
+        assert_eq!(env::promise_results_count(), 2, "ERR_TOO_MANY_RESULTS");
+        let is_registered = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<Option<StorageBalance>>(&value).map_or(false, |storage_balance| storage_balance.is_some()),
+            PromiseResult::Failed => false,
+        };
+        let storage_deposit = if is_registered {
+            0
+        } else {
+            match env::promise_result(1) {
+                PromiseResult::NotReady => unreachable!(),
+                PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<StorageBalanceBounds>(&value).map_or(FT_STORAGE_DEPOSIT, |storage_balance_bounds| storage_balance_bounds.min.0),
+                PromiseResult::Failed => FT_STORAGE_DEPOSIT,
+            }
+        };
+
+        let claims = self.internal_claim_rounds(project_id, &account_id);
+        let claim_amount: Balance = claims.iter().map(|(_, amount)| amount.0).sum();
+        if claim_amount == 0 || env::attached_deposit() < storage_deposit {
+            env::log(format!("Claim failed: account_id={}, receiver_id={}, amount={}, storage_deposit={}", account_id, receiver_id, claim_amount, storage_deposit).as_bytes());
+            return Promise::new(payer_id).transfer(env::attached_deposit());
+        }
+
+        self.internal_record_claims(&account_id, &claims);
+        env::log(format!("Claim: account_id={}, receiver_id={}, amount={}", account_id, receiver_id, claim_amount).as_bytes());
+
+        if env::attached_deposit() > storage_deposit {
+            Promise::new(payer_id).transfer(env::attached_deposit() - storage_deposit);
+        }
+
+        let token_contract_id = self.internal_get_project_or_panic(project_id).token_contract_id;
+        self.internal_transfer_claims(token_contract_id, account_id, receiver_id, claims, if is_registered { None } else { Some(storage_deposit) })
+    }
+
+    /// Transfer the recorded claims of the account to the receiver, the callback rolls them back if the transfer fails.
+    /// The receiver is registered on the token before the transfer if the storage deposit is set.
+    pub(crate) fn internal_transfer_claims(&self, token_contract_id: AccountId, account_id: AccountId, receiver_id: AccountId, claims: Vec<(ProjectId, U128)>, storage_deposit: Option<Balance>) -> Promise {
+        let claim_amount: Balance = claims.iter().map(|(_, amount)| amount.0).sum();
+
+        let transfer = ext_ft_contract::ft_transfer(
+            receiver_id.clone(),
+            U128(claim_amount),
+            Some(format!("Claim the amount of {} on contract {} from IDO Contract", claim_amount, token_contract_id)),
+            &token_contract_id,
+            DEPOSIT_ONE_YOCTOR,
+            FT_TRANSFER_GAS
+        );
+        let transfer = match storage_deposit {
+            Some(storage_deposit) => ext_ft_storage::storage_deposit(
+                Some(receiver_id),
+                Some(true),
+                &token_contract_id,
+                storage_deposit,
+                FT_STORAGE_DEPOSIT_GAS
+            ).then(transfer),
+            None => transfer,
+        };
+
+        transfer.then(
+            ext_self::ft_transfer_callback(
+                account_id,
+                claims,
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                TRANSFER_CALLBACK_GAS
+            )
+        )
+    }
+
+    /// The claims were recorded before the transfer, they're rolled back if the transfer failed.
+    #[private]
+    pub fn ft_transfer_callback(&mut self, account_id: AccountId, claims: Vec<(ProjectId, U128)>) -> U128 {
+        assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+        let claim_amount: Balance = claims.iter().map(|(_, amount)| amount.0).sum();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_value) => U128(claim_amount),
+            PromiseResult::Failed => {
+                for (project_id, amount) in claims {
+                    self.internal_rollback_claimed_amount(project_id, &account_id, amount.0);
+                }
+                env::log(format!("Claim failed: account_id={}, amount={}", account_id, claim_amount).as_bytes());
+
+                U128(0)
+            },
+        }
+    }
+
+    /// Claim the token and stake it in the staking pool with a lock, the stake is credited to the caller.
+    /// The sale token must be the configured staking token, and the caller must be registered in the staking pool,
+    /// otherwise the staking pool rejects the transfer and nothing is claimed.
+    #[payable]
+    pub fn claim_and_stake(&mut self, project_id: ProjectId, locked_days: u32) -> Promise {
+        let account_id: AccountId = env::predecessor_account_id();
+        let project = self.internal_get_project_or_panic(project_id);
+        let staking_token_id = self.config.staking_token_id.clone().expect("The staking token isn't configured.");
+        assert_eq!(staking_token_id, project.token_contract_id, "The sale token of the project isn't the staking token.");
+        assert!(project.is_in_distribution_period(), "The project isn't in distribution period.");
+        self.assert_not_paused(&project);
+        self.assert_can_claim(project_id, &account_id);
 
         assert_one_yocto();
-        let claim_amount = self.internal_claim(project_id, &account_id);
+        let claims = self.internal_claim_rounds(project_id, &account_id);
+        let claim_amount: Balance = claims.iter().map(|(_, amount)| amount.0).sum();
+        assert!(claim_amount > 0, "The account has nothing to claim.");
+        self.internal_record_claims(&account_id, &claims);
+
+        ext_ft_contract::ft_transfer_call(
+            self.staking_contract_id.clone(),
+            U128(claim_amount),
+            Some(format!("Claim and stake the amount of {} on contract {} from IDO Contract", claim_amount, project.token_contract_id)),
+            format!("lock:{}:{}", locked_days, account_id),
+            &project.token_contract_id,
+            DEPOSIT_ONE_YOCTOR,
+            FT_TRANSFER_CALL_GAS
+        ).then(
+            ext_self::claim_and_stake_callback(
+                account_id,
+                claims,
+                U128(claim_amount),
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                TRANSFER_CALLBACK_GAS
+            )
+        )
+    }
+
+    /// The ft_transfer_call returns the amount that the staking pool used, the rejected token is returned to this contract.
+    /// The claims are recorded before the transfer, so only the amount that wasn't staked is rolled back.
+    #[private]
+    pub fn claim_and_stake_callback(&mut self, account_id: AccountId, claims: Vec<(ProjectId, U128)>, claim_amount: U128) -> U128 {
+        assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+        let staked_amount: Balance = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value).map_or(0, |amount| amount.0),
+            PromiseResult::Failed => 0,
+        };
+        let staked_amount = std::cmp::min(staked_amount, claim_amount.0);
+
+        if staked_amount < claim_amount.0 {
+            let mut unused_amount = claim_amount.0 - staked_amount;
+            for (project_id, amount) in claims.into_iter().rev() {
+                if unused_amount == 0 {
+                    break;
+                }
+                let rollback_amount = std::cmp::min(amount.0, unused_amount);
+                self.internal_rollback_claimed_amount(project_id, &account_id, rollback_amount);
+                unused_amount -= rollback_amount;
+            }
+
+            env::log(format!("Claim and stake failed: account_id={}, amount={}, staked_amount={}", account_id, claim_amount.0, staked_amount).as_bytes());
+        }
+
+        U128(staked_amount)
+    }
+
+    pub(crate) fn internal_record_claims(&mut self, account_id: &AccountId, claims: &[(ProjectId, U128)]) {
+        for (project_id, claim_amount) in claims {
+            self.internal_update_claimed_amount(*project_id, account_id, claim_amount.0);
+        }
+    }
+
+    /// Mark the claimed token of an account in a project before it's transferred.
+    pub(crate) fn internal_update_claimed_amount(&mut self, project_id: ProjectId, account_id: &AccountId, claim_amount: Balance) {
+        self.internal_update_project_stats(project_id, |project_stats| project_stats.total_claimed_amount += claim_amount);
+
+        let mut project_account_unordered_map = self.internal_get_accounts_by_project_or_panic(project_id);
+        let mut project_account_1 = self.internal_get_account_by_project_or_panic(project_id,&account_id);
+        let account_distribution = project_account_1.distribution_data;
+        if let Some(mut account_distribution) = account_distribution{
+            account_distribution.claimed_amount += claim_amount;
+            project_account_1.distribution_data = Some(account_distribution);
+            project_account_unordered_map.insert(&account_id,&UpgradableProjectAccount::from(project_account_1));
+            self.accounts_by_project.insert(&project_id,&project_account_unordered_map);
+        }
+    }
+
+    /// Roll back the claimed token of an account in a project if its transfer failed.
+    pub(crate) fn internal_rollback_claimed_amount(&mut self, project_id: ProjectId, account_id: &AccountId, claim_amount: Balance) {
+        let mut project_account = self.internal_get_account_by_project_or_panic(project_id, account_id);
+        if let Some(mut account_distribution) = project_account.distribution_data {
+            account_distribution.claimed_amount -= claim_amount;
+            project_account.distribution_data = Some(account_distribution);
+        }
+        self.internal_save_account_by_project(project_id, account_id, project_account);
+        self.internal_update_project_stats(project_id, |project_stats| project_stats.total_claimed_amount -= claim_amount);
+    }
+
+    /// User can claim back the fund that was not used after sales. Ex: the difference between the bid and the clearing price.
+    #[payable]
+    pub fn claim_refund(&mut self, project_id: ProjectId) -> Promise {
+        let account_id: AccountId = env::predecessor_account_id();
+        let project = self.internal_get_project_or_panic(project_id);
+        assert!(project.is_in_distribution_period(), "The project isn't in distribution period.");
+        self.assert_not_paused(&project);
+        self.assert_can_claim(project_id, &account_id);
+
+        assert_one_yocto();
+        let refund_amount = self.internal_refund(project_id, &account_id);
+
+        project.get_fund_asset().transfer(
+            account_id.clone(),
+            refund_amount,
+            format!("Refund the amount of {} on contract {} from IDO Contract", refund_amount, project.fund_contract_id)
+        ).then(
+            ext_self::refund_callback(
+                project_id,
+                account_id,
+                U128(refund_amount),
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                TRANSFER_CALLBACK_GAS
+            )
+        )
+    }
+
+    /// The refunded amount was updated before the transfer, it's rolled back if the transfer failed.
+    #[private]
+    pub fn refund_callback(&mut self, project_id: ProjectId, account_id: AccountId, refund_amount: U128) -> U128 {
+        assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_value) => refund_amount,
+            PromiseResult::Failed => {
+                let mut project_account = self.internal_get_account_by_project_or_panic(project_id, &account_id);
+                if let Some(mut account_distribution) = project_account.distribution_data {
+                    account_distribution.refunded_amount -= refund_amount.0;
+                    project_account.distribution_data = Some(account_distribution);
+                    self.internal_save_account_by_project(project_id, &account_id, project_account);
+                }
+                self.internal_update_project_stats(project_id, |project_stats| project_stats.total_refunded_amount -= refund_amount.0);
+                env::log(format!("Refund failed: project_id={}, account_id={}, amount={}", project_id, account_id, refund_amount.0).as_bytes());
+
+                U128(0)
+            },
+        }
+    }
+
+    /// The project owner withdraws the unsold tokens after the project is distributed,
+    /// or burns them by transferring them to the burn account of the contract.
+    #[payable]
+    pub fn withdraw_unsold_tokens(&mut self, project_id: ProjectId, burn: bool) -> Promise {
+        assert_one_yocto();
+        let mut project = self.internal_get_project_or_panic(project_id);
+        assert_eq!(env::predecessor_account_id(), project.owner_id, "You are not the owner of this project.");
+        assert_eq!(project.status, ProjectStatus::Distribution, "The project isn't distributed.");
+        assert!(project.unsold_token_amount > 0, "The project has no unsold tokens.");
+        assert!(project.unsold_token_receiver_id.is_none(), "The unsold tokens are already withdrawn.");
+
+        let receiver_id: AccountId = if burn {
+            self.config.burn_account_id.clone().expect("The burn account isn't configured.")
+        } else {
+            project.owner_id.clone()
+        };
+        let unsold_token_amount = project.unsold_token_amount;
+        let token_contract_id = project.token_contract_id.clone();
+        project.unsold_token_receiver_id = Some(receiver_id.clone());
+        self.internal_save_project(project_id, project);
 
-        // TODO: Update the claimed amount in the project_account before starting to transfer token to user.
-        // If the cross-call transaction failed, it's need to roll back the data that was updated.
-        
-        // handle transfer withdraw
         ext_ft_contract::ft_transfer(
-            account_id.clone(), 
-            U128(claim_amount), 
-            Some(format!("Claim the amount of {} on contract {} from IDO Contract", claim_amount, project.token_contract_id)), 
-            &project.token_contract_id, 
-            DEPOSIT_ONE_YOCTOR, 
+            receiver_id,
+            U128(unsold_token_amount),
+            Some(format!("Withdraw the unsold amount of {} on contract {} from IDO Contract", unsold_token_amount, token_contract_id)),
+            &token_contract_id,
+            DEPOSIT_ONE_YOCTOR,
             FT_TRANSFER_GAS
         ).then(
-            ext_self::ft_transfer_callback(
+            ext_self::unsold_tokens_callback(
                 project_id,
-                account_id.clone(),
-                U128(claim_amount),
                 &env::current_account_id(),
-                NO_DEPOSIT, 
+                NO_DEPOSIT,
+                TRANSFER_CALLBACK_GAS
+            )
+        )
+    }
+
+    /// The receiver of the unsold tokens was recorded before the transfer, it's cleared if the transfer failed.
+    #[private]
+    pub fn unsold_tokens_callback(&mut self, project_id: ProjectId) -> U128 {
+        assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+        let mut project = self.internal_get_project_or_panic(project_id);
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_value) => U128(project.unsold_token_amount),
+            PromiseResult::Failed => {
+                env::log(format!("Withdraw unsold tokens failed: project_id={}, receiver_id={:?}", project_id, project.unsold_token_receiver_id).as_bytes());
+                project.unsold_token_receiver_id = None;
+                self.internal_save_project(project_id, project);
+
+                U128(0)
+            },
+        }
+    }
+
+    /// The project owner withdraws the fund of the sold tokens after the project is distributed,
+    /// the fund that is refundable to the accounts stays in the contract.
+    #[payable]
+    pub fn withdraw_fund(&mut self, project_id: ProjectId) -> Promise {
+        assert_one_yocto();
+        let mut project = self.internal_get_project_or_panic(project_id);
+        assert_eq!(env::predecessor_account_id(), project.owner_id, "You are not the owner of this project.");
+        assert_eq!(project.status, ProjectStatus::Distribution, "The project isn't distributed.");
+
+        let total_refund_amount = self.internal_get_project_stats_or_default(project_id).total_refund_amount;
+        let withdraw_amount = project.total_fund_committed
+            .saturating_sub(total_refund_amount)
+            .saturating_sub(project.withdrawn_fund_amount);
+        assert!(withdraw_amount > 0, "The project has no fund to withdraw.");
+
+        project.withdrawn_fund_amount += withdraw_amount;
+        let owner_id = project.owner_id.clone();
+        let fund_asset = project.get_fund_asset();
+        let fund_contract_id = project.fund_contract_id.clone();
+        self.internal_save_project(project_id, project);
+
+        fund_asset.transfer(
+            owner_id,
+            withdraw_amount,
+            format!("Withdraw the fund of {} on contract {} from IDO Contract", withdraw_amount, fund_contract_id)
+        ).then(
+            ext_self::withdraw_fund_callback(
+                project_id,
+                U128(withdraw_amount),
+                &env::current_account_id(),
+                NO_DEPOSIT,
                 TRANSFER_CALLBACK_GAS
             )
         )
     }
 
+    /// The withdrawn fund was recorded before the transfer, it's restored if the transfer failed.
     #[private]
-    pub fn ft_transfer_callback(&mut self, project_id: ProjectId, account_id: AccountId, claim_amount: U128) -> U128 {
+    pub fn withdraw_fund_callback(&mut self, project_id: ProjectId, withdraw_amount: U128) -> U128 {
         assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
         match env::promise_result(0) {
             PromiseResult::NotReady => unreachable!(),
-            PromiseResult::Successful(_value) => {
-                let mut project_account_unordered_map = self.internal_get_accounts_by_project_or_panic(project_id);
-                let mut project_account_1 = self.internal_get_account_by_project_or_panic(project_id,&account_id);
-                let mut project_account_2 = self.internal_get_account_by_project_or_panic(project_id,&account_id);
-                let account_distribution = project_account_1.distribution_data;
-                if let Some(mut account_distribution) = account_distribution{
-                    account_distribution.claimed_amount += claim_amount.0;
-                    project_account_1.distribution_data = Some(account_distribution);
-                    project_account_unordered_map.insert(&account_id,&project_account_1);
-                    self.accounts_by_project.insert(&project_id,&project_account_unordered_map);
-                } else {
-                    let mut account_sale = project_account_2.sale_data.unwrap();
-                    account_sale.committed_amount = 0;
-                    project_account_2.sale_data = Some(account_sale);
-                    project_account_unordered_map.insert(&account_id,&project_account_2);
-                    self.accounts_by_project.insert(&project_id,&project_account_unordered_map);
+            PromiseResult::Successful(_value) => withdraw_amount,
+            PromiseResult::Failed => {
+                let mut project = self.internal_get_project_or_panic(project_id);
+                project.withdrawn_fund_amount -= withdraw_amount.0;
+                self.internal_save_project(project_id, project);
+                env::log(format!("Withdraw fund failed: project_id={}, amount={}", project_id, withdraw_amount.0).as_bytes());
+
+                U128(0)
+            },
+        }
+    }
+
+    pub(crate) fn internal_refund(&mut self, project_id: ProjectId, account_id: &AccountId) -> Balance {
+        let mut project_account = self.internal_get_account_by_project_or_panic(project_id, account_id);
+        let mut account_distribution = project_account.distribution_data.expect("The account has nothing to refund.");
+
+        let refund_amount = account_distribution.refund_amount - account_distribution.refunded_amount;
+        assert!(refund_amount > 0, "The account has nothing to refund.");
+
+        account_distribution.refunded_amount += refund_amount;
+        project_account.distribution_data = Some(account_distribution);
+        self.internal_save_account_by_project(project_id, account_id, project_account);
+        self.internal_update_project_stats(project_id, |project_stats| project_stats.total_refunded_amount += refund_amount);
+
+        refund_amount
+    }
+
+    /// Load the decimals of the sale token, and the fund token if it's not NEAR, the loaded decimals are skipped.
+    /// The decimals are loaded on the main project because the rounds share its tokens.
+    pub(crate) fn internal_load_project_metadata(&self, project_id: ProjectId) {
+        let project = self.internal_get_project_or_panic(project_id);
+        let project_id = project.parent_id.unwrap_or(project_id);
+
+        let mut token_contract_ids = vec![];
+        if project.token_decimals.is_none() {
+            token_contract_ids.push((project.token_contract_id, false));
+        }
+        if project.fund_decimals.is_none() {
+            token_contract_ids.push((project.fund_contract_id, true));
+        }
+
+        for (token_contract_id, is_fund_token) in token_contract_ids {
+            ext_ft_metadata::ft_metadata(
+                &token_contract_id,
+                NO_DEPOSIT,
+                FT_METADATA_GAS
+            ).then(
+                ext_self::resolve_ft_metadata(
+                    project_id,
+                    is_fund_token,
+                    &env::current_account_id(),
+                    NO_DEPOSIT,
+                    FT_METADATA_CALLBACK_GAS
+                )
+            );
+        }
+    }
+
+    #[private]
+    pub fn resolve_ft_metadata(&mut self, project_id: ProjectId, is_fund_token: bool) -> Option<u8> {
+        assert_eq!(env::promise_results_count(), 1, "This is a callback method");
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Failed => {
+                // The project is blocked until the metadata is loaded again or the decimals are set by an admin
+                env::log(format!("Failed to load the {} metadata of project {}.", if is_fund_token { "fund token" } else { "sale token" }, project_id).as_bytes());
+                None
+            },
+            PromiseResult::Successful(result) => {
+                let metadata = near_sdk::serde_json::from_slice::<FungibleTokenMetadata>(&result).expect("Invalid token metadata.");
+                // The decimals set by an admin while the metadata was loading are kept
+                let project = self.internal_get_project_or_panic(project_id);
+                let decimals = if is_fund_token { project.fund_decimals } else { project.token_decimals };
+                if decimals.is_none() {
+                    self.internal_set_project_decimals(project_id, is_fund_token, metadata.decimals);
                 }
-                
-                U128::from(0)
+
+                Some(metadata.decimals)
             },
-            PromiseResult::Failed => env::panic(b"ERR_CALL_FAILED"),
         }
     }
 
-    pub(crate) fn internal_claim(&mut self, project_id: ProjectId, account_id: &AccountId) -> Balance{
+    /// The rounds share the tokens of the main project, so they share the decimals too.
+    pub(crate) fn internal_set_project_decimals(&mut self, project_id: ProjectId, is_fund_token: bool, decimals: u8) {
+        let project = self.internal_get_project_or_panic(project_id);
+        let main_project_id = project.parent_id.unwrap_or(project_id);
+        let main_project = self.internal_get_project_or_panic(main_project_id);
+
+        for round_id in std::iter::once(main_project_id).chain(main_project.round_ids.into_iter()) {
+            let mut round = self.internal_get_project_or_panic(round_id);
+            if is_fund_token {
+                round.fund_decimals = Some(decimals);
+            } else {
+                round.token_decimals = Some(decimals);
+            }
+            self.internal_save_project(round_id, round);
+        }
+    }
+
+    /// The claimable token of the project and its rounds, the rounds that are not distributed or paused are skipped.
+    pub(crate) fn internal_claim_rounds(&self, project_id: ProjectId, account_id: &AccountId) -> Vec<(ProjectId, U128)> {
+        let project = self.internal_get_project_or_panic(project_id);
+        let main_project_id = project.parent_id.unwrap_or(project_id);
+        let main_project = self.internal_get_project_or_panic(main_project_id);
+
+        std::iter::once(main_project_id)
+            .chain(main_project.round_ids.into_iter())
+            .filter(|round_id| {
+                let round = self.internal_get_project_or_panic(*round_id);
+                round.is_in_distribution_period() && !self.is_project_paused(&round)
+                    && self.internal_get_account_by_project(*round_id, account_id).map_or(false, |account| account.sale_data.is_some())
+                    // The owner of the vesting token claims the minted position
+                    && !self.is_vesting_token_minted(*round_id, account_id)
+            })
+            .map(|round_id| (round_id, U128(self.internal_claim(round_id, account_id))))
+            .filter(|(_, claim_amount)| claim_amount.0 > 0)
+            .collect()
+    }
+
+    pub(crate) fn internal_claim(&self, project_id: ProjectId, account_id: &AccountId) -> Balance{
         // Get deposit token amount and project_ft_contract_id
         let project_account = self.internal_get_account_by_project_or_panic(project_id,&account_id);
-        let sale_data = project_account.sale_data.unwrap();
         let distribution_data = project_account.distribution_data;
         // Calculate token to transfer for user
+        // The committed fund isn't a token amount, nothing is claimable until the account is distributed, ex: a lottery before the draw.
+        // The locked tokens of a vested project are claimable when they're released.
         let claim_amount = if let Some(distribution_data) = distribution_data {
-            distribution_data.unlocked_amount - distribution_data.claimed_amount
+            let project = self.internal_get_project_or_panic(project_id);
+            distribution_data.unlocked_amount + project.get_released_amount(distribution_data.locked_amount) - distribution_data.claimed_amount
         } else {
-            sale_data.committed_amount
+            0
         };
         
         claim_amount 
@@ -115,11 +693,14 @@ impl IDOContract {
                 "project_id" => {
                     let project_id : ProjectId = args[1].trim().parse::<u64>().unwrap();
                     let project = self.internal_get_project_or_panic(project_id);
-                    let fund_contract_id = env::predecessor_account_id();
-                    if project.fund_contract_id == fund_contract_id{ 
+                    let fund_asset = FundAsset::FungibleToken(env::predecessor_account_id());
+                    if self.is_project_paused(&project) {
+                        env::log(b"Transfer Error: the project is paused. Transfer back deposited token to signer");
+                        return PromiseOrValue::Value(amount);
+                    } else if project.accepts_fund_asset(&fund_asset) {
                         env::log(format!("Ft on transfer success: project_id={},sender_id={},amount={},fund_contract_id={}", project_id, sender_id, amount.0,env::predecessor_account_id()).as_bytes());
-                        let committed = self.internal_commit(project_id, &sender_id, amount.0);
-                        return PromiseOrValue::Value(U128(amount.0 - committed));
+                        let unaccepted_amount = self.internal_commit_fund(project_id, &sender_id, fund_asset, amount.0);
+                        return PromiseOrValue::Value(U128(unaccepted_amount));
                     } else {
                         env::log(b"Transfer Error: fund_contract_id not match. Transfer back deposited token to signer");
                         return PromiseOrValue::Value(amount);
@@ -1,22 +1,33 @@
 use near_sdk::{init, env, near_bindgen, ext_contract};
-use near_sdk::{PanicOnDefault, Timestamp, Balance, AccountId, CryptoHash, Promise, PromiseOrValue, PromiseResult, EpochHeight};
+use near_sdk::{PanicOnDefault, PublicKey, Timestamp, Balance, AccountId, BlockHeight, CryptoHash, Promise, PromiseOrValue, PromiseResult, EpochHeight};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::collections::{UnorderedMap, UnorderedSet, LookupMap};
 use near_sdk::env::signer_account_id;
-use near_sdk::json_types::{U128, U64};
+use near_sdk::json_types::{Base58PublicKey, U128, U64};
 use std::collections::HashMap;
 
 pub type ProjectId = u64;
 pub type AllocationNumber = u32;
 pub type TicketNumber = u64;
 pub type TicketId = u64;
-pub type ProjectAccountUnorderedMap = UnorderedMap<AccountId, ProjectAccount>;
+pub type ProjectAccountUnorderedMap = UnorderedMap<AccountId, UpgradableProjectAccount>;
 pub type TicketAndAccountLookupMap = LookupMap<TicketId, AccountId>;
 pub type ProjectIdUnorderedSet = UnorderedSet<ProjectId>;
 
 use crate::modules::project::*;
 use crate::modules::account::*;
+use crate::modules::ticket::*;
+use crate::modules::storage::*;
+use crate::modules::round::*;
+use crate::modules::kyc::*;
+use crate::modules::fund::*;
+use crate::modules::stats::*;
+use crate::modules::lottery::LotteryDraw;
+use crate::modules::claim::*;
+use crate::modules::vesting::*;
+use crate::modules::leftover::*;
+use crate::modules::blacklist::*;
 use crate::utils::*;
 use crate::staking_contract::*;
 use crate::ft_contract::*;
@@ -24,12 +35,16 @@ use crate::modules::tier::{Tier, TierConfig, TierConfigsType, UserTierJson};
 
 pub mod modules;
 mod utils;
+mod migration;
 mod tests;
 pub mod staking_contract;
 mod ft_contract;
 
 pub const DEFAULT_PAGE_SIZE: u64 = 100;
 pub const TOKEN_DECIMAL: u8 = 8;
+pub const NEAR_DECIMALS: u8 = 24;
+pub const MAX_RATE_FRACTION_DIGITS: usize = 24;
+pub const DEFAULT_LOTTERY_WEIGHT: u32 = 100;
 
 pub const GAS_FUNCTION_CALL: u64 = 5_000_000_000_000;
 pub const GAS_FUNCTION_CALL_UPDATE_STAKING_TIER: u64 = 50_000_000_000_000;
@@ -52,6 +67,16 @@ pub enum StorageKey {
     TierTicketInnerKey (String),
     TierAllocationInnerKey (String),
     TierConfigsKey,
+    StorageAccountKey,
+    AdminKey,
+    TimeOffsetKey,
+    ProjectStatsKey,
+    ClaimDelegatesKey,
+    VestingTokensKey,
+    BlacklistKey,
+    ProjectBlacklistsKey,
+    ProjectBlacklistInnerKey(ProjectId),
+    AccountMigrationsKey,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone)]
@@ -59,12 +84,28 @@ pub enum StorageKey {
 pub struct Config {
     /// the config for each user Tier
     pub tier_configs: TierConfigsType,
+    /// The ed25519 public keys of the KYC providers that sign the KYC attestations
+    #[serde(default)]
+    pub kyc_provider_public_keys: Vec<PublicKey>,
+    /// The account that the burnt unsold tokens are transferred to.
+    #[serde(default)]
+    pub burn_account_id: Option<AccountId>,
+    /// What the blacklisted accounts can't do.
+    #[serde(default)]
+    pub blacklist_policy: BlacklistPolicy,
+    /// The token of the staking pool, only the projects that sell it can be claimed and staked.
+    #[serde(default)]
+    pub staking_token_id: Option<AccountId>,
 }
 
 impl Config {
     fn new_default_config() -> Self {
         Self {
             tier_configs: TierConfig::get_default_tier_configs(),
+            kyc_provider_public_keys: vec![],
+            burn_account_id: None,
+            blacklist_policy: BlacklistPolicy::default(),
+            staking_token_id: None,
         }
     }
 
@@ -73,6 +114,10 @@ impl Config {
     ) -> Self {
         Self {
             tier_configs,
+            kyc_provider_public_keys: vec![],
+            burn_account_id: None,
+            blacklist_policy: BlacklistPolicy::default(),
+            staking_token_id: None,
         }
     }
 }
@@ -96,7 +141,7 @@ pub struct IDOContract{
     pub funding_ft_token_ids: UnorderedSet<AccountId>,
 
     /// Stores the list of projects that belongs to this IDO contract.
-    pub projects: UnorderedMap<ProjectId, Project>,
+    pub projects: UnorderedMap<ProjectId, UpgradableProject>,
 
     /// Stores the list of accounts for each project.
     /// Each account contains the Sale and Distribution data.
@@ -116,6 +161,34 @@ pub struct IDOContract{
     pub test_mode_enabled: bool,
 
     pub config: Config,
+
+    /// The storage deposit and usage of each account (NEP-145).
+    /// The storage of whitelist registration, commits and tickets is paid by the account.
+    pub storage_accounts: LookupMap<AccountId, StorageAccount>,
+
+    /// The accounts that can do the admin actions like pausing the contract or a project.
+    pub admin_ids: UnorderedSet<AccountId>,
+
+    /// Pause all whitelist registrations, commits and claims of all projects.
+    pub paused: bool,
+
+    /// The sale statistics of each project.
+    pub project_stats: LookupMap<ProjectId, ProjectStats>,
+
+    /// The accounts that each account authorized to claim its tokens.
+    pub claim_delegates: LookupMap<AccountId, Vec<AccountId>>,
+
+    /// The vesting positions that are minted as NEP-171 tokens.
+    pub vesting_tokens: UnorderedMap<VestingTokenId, VestingToken>,
+
+    /// The accounts that are blacklisted in all projects.
+    pub blacklist: UnorderedSet<AccountId>,
+
+    /// The accounts that are blacklisted in each project.
+    pub project_blacklists: LookupMap<ProjectId, UnorderedSet<AccountId>>,
+
+    /// The projects whose accounts are still in the layout before the migration, and the number of their migrated accounts.
+    pub account_migrations: UnorderedMap<ProjectId, u64>,
 }
 
 #[near_bindgen]
@@ -138,6 +211,15 @@ impl IDOContract {
             projects_by_account: LookupMap::new(get_storage_key(StorageKey::ProjectsByAccountKey)),
             test_mode_enabled: test_mode_enabled.unwrap_or(true),
             config: config.unwrap_or(Config::default()),
+            storage_accounts: LookupMap::new(get_storage_key(StorageKey::StorageAccountKey)),
+            admin_ids: UnorderedSet::new(get_storage_key(StorageKey::AdminKey)),
+            paused: false,
+            project_stats: LookupMap::new(get_storage_key(StorageKey::ProjectStatsKey)),
+            claim_delegates: LookupMap::new(get_storage_key(StorageKey::ClaimDelegatesKey)),
+            vesting_tokens: UnorderedMap::new(get_storage_key(StorageKey::VestingTokensKey)),
+            blacklist: UnorderedSet::new(get_storage_key(StorageKey::BlacklistKey)),
+            project_blacklists: LookupMap::new(get_storage_key(StorageKey::ProjectBlacklistsKey)),
+            account_migrations: UnorderedMap::new(get_storage_key(StorageKey::AccountMigrationsKey)),
         };
 
         if let Some(funding_ft_token_ids) = funding_ft_token_ids {
@@ -146,6 +228,10 @@ impl IDOContract {
             }
         }
 
+        if !contract.test_mode_enabled {
+            clear_time_offset();
+        }
+
         contract
     }
 
@@ -161,10 +247,187 @@ impl IDOContract {
         self.owner_id = owner_id;
     }
 
+    pub fn add_admin(&mut self, account_id: AccountId) {
+        self.assert_owner();
+
+        self.admin_ids.insert(&account_id);
+    }
+
+    pub fn remove_admin(&mut self, account_id: AccountId) {
+        self.assert_owner();
+
+        self.admin_ids.remove(&account_id);
+    }
+
+    pub fn get_admin_ids(&self) -> Vec<AccountId> {
+        self.admin_ids.to_vec()
+    }
+
+    // Emergency pause
+
+    /// Pause or unpause all projects. Views are still available while the contract is paused.
+    /// The contract can't be unpaused until the accounts of all projects are migrated.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.assert_admin();
+        assert!(paused || self.account_migrations.is_empty(), "The project accounts aren't migrated yet.");
+
+        self.paused = paused;
+        env::log(format!("Contract paused: {}", paused).as_bytes());
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause or unpause a single project.
+    pub fn set_project_paused(&mut self, project_id: ProjectId, paused: bool) {
+        self.assert_admin();
+
+        let mut project = self.internal_get_project_or_panic(project_id);
+        project.paused = paused;
+        self.internal_save_project(project_id, project);
+        env::log(format!("Project {} paused: {}", project_id, paused).as_bytes());
+    }
+
+    /// Require a KYC attestation to register the whitelist of a project.
+    pub fn set_project_kyc_required(&mut self, project_id: ProjectId, kyc_required: bool) {
+        self.assert_admin();
+
+        let mut project = self.internal_get_project_or_panic(project_id);
+        project.kyc_required = kyc_required;
+        self.internal_save_project(project_id, project);
+        env::log(format!("Project {} kyc_required: {}", project_id, kyc_required).as_bytes());
+    }
+
+    /// Require the stake to be locked for min_stake_days before the whitelist start date of an XToken project.
+    /// The age is measured from the first lock: the staking pool keeps locked_timestamp when the lock is topped up,
+    /// so a small lock made early can be topped up right before the whitelist starts.
+    pub fn set_project_min_stake_days(&mut self, project_id: ProjectId, min_stake_days: u32) {
+        self.assert_admin();
+
+        let mut project = self.internal_get_project_or_panic(project_id);
+        project.min_stake_days = min_stake_days;
+        self.internal_save_project(project_id, project);
+        env::log(format!("Project {} min_stake_days: {}", project_id, min_stake_days).as_bytes());
+    }
+
+    /// Set the release of the locked tokens of a vested project, it must be set before the project is distributed.
+    pub fn set_project_vesting_schedule(&mut self, project_id: ProjectId, vesting_schedule: VestingSchedule) {
+        self.assert_admin();
+        self.internal_set_project_vesting_schedule(project_id, vesting_schedule);
+    }
+
+    /// Configure the second chance sale of the unsold tokens, it starts after the project is distributed.
+    pub fn set_project_leftover_sale(&mut self, project_id: ProjectId, leftover_sale: Option<LeftoverSale>) {
+        self.assert_admin();
+        self.internal_set_project_leftover_sale(project_id, leftover_sale);
+    }
+
+    pub fn add_kyc_provider_key(&mut self, public_key: Base58PublicKey) {
+        self.assert_admin();
+        self.internal_add_kyc_provider_key(public_key.into());
+    }
+
+    pub fn remove_kyc_provider_key(&mut self, public_key: Base58PublicKey) {
+        self.assert_admin();
+        self.internal_remove_kyc_provider_key(public_key.into());
+    }
+
+    pub fn get_kyc_provider_keys(&self) -> Vec<Base58PublicKey> {
+        self.config.kyc_provider_public_keys.iter().map(|public_key| Base58PublicKey(public_key.clone())).collect()
+    }
+
+    /// Blacklist an account in a project, or in all projects if the project is not set.
+    pub fn add_to_blacklist(&mut self, account_id: AccountId, project_id: Option<ProjectId>) {
+        self.assert_admin();
+        self.internal_add_to_blacklist(&account_id, project_id);
+    }
+
+    pub fn remove_from_blacklist(&mut self, account_id: AccountId, project_id: Option<ProjectId>) {
+        self.assert_admin();
+        self.internal_remove_from_blacklist(&account_id, project_id);
+    }
+
+    pub fn get_blacklist(&self, project_id: Option<ProjectId>) -> Vec<AccountId> {
+        self.internal_get_blacklist(project_id)
+    }
+
+    /// The account is blacklisted in the project, its main project or all projects.
+    pub fn is_blacklisted(&self, project_id: ProjectId, account_id: AccountId) -> bool {
+        self.internal_is_blacklisted(project_id, &account_id)
+    }
+
+    pub fn set_blacklist_policy(&mut self, blacklist_policy: BlacklistPolicy) {
+        self.assert_admin();
+        self.config.blacklist_policy = blacklist_policy;
+    }
+
+    pub fn get_blacklist_policy(&self) -> BlacklistPolicy {
+        self.config.blacklist_policy
+    }
+
+    /// The unsold tokens that a project owner burns are transferred to this account.
+    pub fn set_burn_account_id(&mut self, burn_account_id: Option<AccountId>) {
+        self.assert_owner();
+        self.config.burn_account_id = burn_account_id;
+    }
+
+    pub fn get_burn_account_id(&self) -> Option<AccountId> {
+        self.config.burn_account_id.clone()
+    }
+
+    /// The token of the staking pool at staking_contract_id, the sale token of a project must match it to be claimed and staked.
+    pub fn set_staking_token_id(&mut self, staking_token_id: Option<AccountId>) {
+        self.assert_owner();
+        self.config.staking_token_id = staking_token_id;
+    }
+
+    pub fn get_staking_token_id(&self) -> Option<AccountId> {
+        self.config.staking_token_id.clone()
+    }
+
     // Project call functions
 
+    /// The decimals of the sale token and the fund token are loaded from their ft_metadata after the project is created.
     pub fn create_project(&mut self, project: ProjectInput) -> ProjectId{
-        self.internal_create_project(Project::from(project))
+        let project_id = self.internal_create_project(Project::from(project));
+        self.internal_load_project_metadata(project_id);
+
+        project_id
+    }
+
+    /// Load the decimals of the tokens again if the ft_metadata call failed when the project was created.
+    pub fn load_project_metadata(&mut self, project_id: ProjectId) {
+        self.assert_admin();
+        self.internal_load_project_metadata(project_id);
+    }
+
+    /// Set the decimals of a token that doesn't implement ft_metadata.
+    /// The loaded decimals can only be changed before the whitelist starts.
+    pub fn set_project_decimals(&mut self, project_id: ProjectId, token_decimals: Option<u8>, fund_decimals: Option<u8>) {
+        self.assert_admin();
+        let project = self.internal_get_project_or_panic(project_id);
+        let can_change = |decimals: Option<u8>| project.status == ProjectStatus::Preparation || decimals.is_none();
+        assert!(token_decimals.is_none() || can_change(project.token_decimals), "The loaded decimals can't be changed after the project starts.");
+        assert!(fund_decimals.is_none() || can_change(project.fund_decimals), "The loaded decimals can't be changed after the project starts.");
+
+        if let Some(token_decimals) = token_decimals {
+            self.internal_set_project_decimals(project_id, false, token_decimals);
+        }
+        if let Some(fund_decimals) = fund_decimals {
+            self.internal_set_project_decimals(project_id, true, fund_decimals);
+        }
+    }
+
+    /// Add a sale round after the last round of a project, the round has its own whitelist, sale and price.
+    pub fn add_project_round(&mut self, project_id: ProjectId, round: ProjectRoundInput) -> ProjectId {
+        self.assert_owner();
+        self.internal_add_project_round(project_id, round)
+    }
+
+    /// Get the main project and its rounds in the sale order
+    pub fn get_project_rounds(&self, project_id: ProjectId) -> Vec<ProjectJson> {
+        self.internal_get_project_rounds(project_id)
     }
 
     pub fn change_project_status(&mut self, project_id: ProjectId) {
@@ -176,6 +439,7 @@ impl IDOContract {
     pub fn get_projects(&self, status: Option<ProjectStatus>, from_index: Option<u64>, limit: Option<u64>) -> Vec<ProjectJson>{
         self.projects
         .iter()
+        .map(|(project_id, project)| (project_id, Project::from(project)))
         .filter(|(_, project)| match &status { None => true, Some(s) => &project.status == s })
         .skip(from_index.unwrap_or(0) as usize)
         .take(limit.unwrap_or(DEFAULT_PAGE_SIZE) as usize)
@@ -184,22 +448,80 @@ impl IDOContract {
     }
 
     pub fn get_project(&self, project_id: ProjectId) -> Option<ProjectJson> {
-        let project = self.projects.get(&project_id);
+        let project = self.projects.get(&project_id).map(Project::from);
 
         self.internal_get_project(project_id, project)
     }
 
+    pub fn get_project_stats(&self, project_id: ProjectId) -> ProjectStatsJson {
+        self.internal_get_project_stats(project_id)
+    }
+
     pub fn get_project_account_info(&self, project_id: ProjectId, account_id: AccountId) -> ProjectAccountJson {
         self.internal_get_project_account_info(project_id, account_id)
     }
 
+    // Project Tickets
+
+    /// Get the account that owns a ticket of a lottery project
+    pub fn get_ticket_owner(&self, project_id: ProjectId, ticket_id: TicketId) -> Option<AccountId> {
+        self.internal_get_ticket_owner(project_id, ticket_id)
+    }
+
+    /// Get the list of ticket ids that an account deposited in a lottery project
+    pub fn get_account_tickets(&self, project_id: ProjectId, account_id: AccountId) -> Vec<TicketId> {
+        self.internal_get_account_tickets(project_id, &account_id)
+    }
+
+    /// Page through all tickets of a lottery project by ticket id
+    pub fn get_project_tickets(&self, project_id: ProjectId, from_index: Option<u64>, limit: Option<u64>) -> Vec<TicketJson> {
+        self.internal_get_project_tickets(project_id, from_index, limit)
+    }
+
+    /// Get the drawn win tickets and their owners so that the lottery result can be verified
+    pub fn get_win_tickets(&self, project_id: ProjectId) -> Vec<TicketJson> {
+        self.internal_get_win_tickets(project_id)
+    }
+
     // Project Whitelist
 
     /// Register an account for a project's whitelist
     /// User can only register the whitelist on the whitelist period of the project
-    pub fn register_whitelist(&mut self, project_id: ProjectId) {
+    /// The KYC attestation is required if the project requires KYC
+    pub fn register_whitelist(&mut self, project_id: ProjectId, kyc_attestation: Option<KycAttestation>) {
+        let account_id = env::signer_account_id();
+        self.internal_register_whitelist(account_id,project_id, kyc_attestation);
+    }
+
+    /// Submit the sealed bid of a batch auction during the sales period, the fund is committed separately.
+    /// bid_hash is the hex encoded sha256 of "{rate_numberator}:{token_amount}:{salt}"
+    pub fn submit_bid_hash(&mut self, project_id: ProjectId, bid_hash: String) {
         let account_id = env::signer_account_id();
-        self.internal_register_whitelist(account_id,project_id);
+        self.internal_submit_bid_hash(project_id, &account_id, bid_hash);
+    }
+
+    /// Reveal the sealed bid of a batch auction during the reveal period
+    pub fn reveal_bid(&mut self, project_id: ProjectId, rate_numberator: u64, token_amount: U128, salt: String) {
+        let account_id = env::signer_account_id();
+        self.internal_reveal_bid(project_id, &account_id, rate_numberator, token_amount.0, salt);
+    }
+
+    /// Commit the hex encoded sha256 of a secret before the sale of a lottery project ends.
+    pub fn commit_lottery_secret(&mut self, project_id: ProjectId, secret_hash: String) {
+        self.assert_admin();
+        self.internal_commit_lottery_secret(project_id, secret_hash);
+    }
+
+    /// Reveal the secret of a lottery project after the sale ends.
+    pub fn reveal_lottery_secret(&mut self, project_id: ProjectId, secret: String) {
+        self.assert_admin();
+        self.internal_reveal_lottery_secret(project_id, secret);
+    }
+
+    /// Anyone can record the random seed of a block after the reveal until all block seeds of the lottery are recorded.
+    /// If the secret isn't revealed within LOTTERY_REVEAL_PERIOD after the sale ends, the block seeds are recorded without it.
+    pub fn record_lottery_block_seed(&mut self, project_id: ProjectId) {
+        self.internal_record_lottery_block_seed(project_id);
     }
 
     /// Check an account wherever registered for a project or not
@@ -223,7 +545,7 @@ impl IDOContract {
         let account_id = env::signer_account_id();
 
         // Verify project & account before calling to staking smart contrct
-        let project = self.projects.get(&project_id);
+        let project = self.projects.get(&project_id).map(Project::from);
         if let Some(project) = project {
             project.assert_whitelist_period();
 
@@ -239,6 +561,7 @@ impl IDOContract {
         println!("close_project_whitelist - inside");
         // Get project
         let mut project = self.internal_get_project_or_panic(project_id);
+        project.assert_decimals_loaded();
         let current_time = get_current_time();
 
         println!("close_project_whitelist - get_current_time");
@@ -250,21 +573,42 @@ impl IDOContract {
         println!("close_project_whitelist - end of for");
 
         project.status = ProjectStatus::Sales;
-        self.projects.insert(&project_id, &project);
+        self.internal_save_project(project_id, project);
     }
+    /// Commit the attached NEAR to a project whose fund is NEAR, the unaccepted amount is transferred back.
+    /// A panic returns the whole attached deposit.
     #[payable]
     pub fn commit(&mut self, project_id: ProjectId){
         let project = self.internal_get_project_or_panic(project_id);
+        self.assert_not_paused(&project);
         let deposit_amount = env::attached_deposit();
         let account_id = env::signer_account_id();
-        if project.fund_contract_id == ""{
-            let committed = self.internal_commit(project_id, &account_id, deposit_amount);
-            Promise::new(account_id).transfer(deposit_amount - committed);
-        } else {
-            Promise::new(account_id).transfer(deposit_amount);
-            env::log(b"Incorrect fund_contract_id");
-        }   
+
+        let unaccepted_amount = self.internal_commit_fund(project_id, &account_id, FundAsset::Near, deposit_amount);
+        if unaccepted_amount > 0 {
+            FundAsset::Near.transfer(account_id, unaccepted_amount, String::new());
+        }
+    }
+
+    // Claim delegates
+
+    /// Authorize another account to claim the tokens of the caller, ex: a cold wallet or a DAO.
+    #[payable]
+    pub fn add_claim_delegate(&mut self, delegate_id: AccountId) {
+        assert_one_yocto();
+        self.internal_add_claim_delegate(&env::predecessor_account_id(), delegate_id);
+    }
+
+    #[payable]
+    pub fn remove_claim_delegate(&mut self, delegate_id: AccountId) {
+        assert_one_yocto();
+        self.internal_remove_claim_delegate(&env::predecessor_account_id(), &delegate_id);
     }
+
+    pub fn get_claim_delegates(&self, account_id: AccountId) -> Vec<AccountId> {
+        self.internal_get_claim_delegates(&account_id)
+    }
+
     /// get UserTierJson: tier, point, ticket, alloc
     pub fn get_user_tier_info(&self) -> UserTierJson {
         let user: AccountId = env::predecessor_account_id();
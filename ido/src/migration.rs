@@ -0,0 +1,297 @@
+use crate::*;
+
+/// The layout of a project before it was wrapped by UpgradableProject, it's stored as UpgradableProject::V1 after the migration.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ProjectV1 {
+    pub owner_id: AccountId,
+    pub whitelist_start_date: Timestamp,
+    pub whitelist_end_date: Timestamp,
+    pub sale_start_date: Timestamp,
+    pub sale_end_date: Timestamp,
+    pub token_contract_id: AccountId,
+    pub token_raised_amount: Balance,
+    pub token_sale_rate: Rate,
+    pub fund_contract_id: AccountId,
+    pub total_fund_committed: Balance,
+    pub status: ProjectStatus,
+    pub whitelist_type: WhitelistType,
+    pub sale_type: SaleType,
+    pub distribution_type: DistributionType,
+}
+
+impl From<ProjectV1> for Project {
+    fn from(project: ProjectV1) -> Self {
+        let fund_decimals = if project.fund_contract_id == "" { Some(NEAR_DECIMALS) } else { None };
+
+        Self {
+            owner_id: project.owner_id,
+            whitelist_start_date: project.whitelist_start_date,
+            whitelist_end_date: project.whitelist_end_date,
+            sale_start_date: project.sale_start_date,
+            sale_end_date: project.sale_end_date,
+            token_contract_id: project.token_contract_id,
+            token_raised_amount: project.token_raised_amount,
+            token_sale_rate: project.token_sale_rate,
+            fund_contract_id: project.fund_contract_id,
+            total_fund_committed: project.total_fund_committed,
+            status: project.status,
+            whitelist_type: project.whitelist_type,
+            sale_type: project.sale_type,
+            distribution_type: project.distribution_type,
+            paused: false,
+            token_decimals: None,
+            fund_decimals,
+            parent_id: None,
+            round_ids: vec![],
+            kyc_required: false,
+            lottery_seed: None,
+            lottery_draw: None,
+            min_stake_days: 0,
+            vesting_schedule: None,
+            unsold_token_amount: 0,
+            unsold_token_receiver_id: None,
+            leftover_sale: None,
+            withdrawn_fund_amount: 0,
+        }
+    }
+}
+
+/// The layout of an account distribution before refunds were supported.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct AccountDistributionV1 {
+    pub unlocked_amount: Balance,
+    pub locked_amount: Balance,
+    pub claimed_amount: Balance,
+}
+
+impl From<AccountDistributionV1> for AccountDistribution {
+    fn from(account_distribution: AccountDistributionV1) -> Self {
+        Self {
+            unlocked_amount: account_distribution.unlocked_amount,
+            locked_amount: account_distribution.locked_amount,
+            claimed_amount: account_distribution.claimed_amount,
+            refund_amount: 0,
+            refunded_amount: 0,
+            leftover_committed_amount: 0,
+        }
+    }
+}
+
+/// The layout of the lottery sale data before the tickets were weighted by tier.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct LotteryAccountSaleDataV1 {
+    pub eligible_tickets: TicketNumber,
+    pub deposit_tickets: TicketNumber,
+    pub ticket_ids: Vec<TicketNumber>,
+    pub win_ticket_ids: Vec<TicketNumber>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum AccountSaleDataV1 {
+    Shared,
+    Lottery(LotteryAccountSaleDataV1),
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct AccountSaleV1 {
+    pub committed_amount: Balance,
+    pub sale_data: AccountSaleDataV1,
+}
+
+impl From<AccountSaleV1> for AccountSale {
+    fn from(account_sale: AccountSaleV1) -> Self {
+        Self {
+            committed_amount: account_sale.committed_amount,
+            sale_data: match account_sale.sale_data {
+                AccountSaleDataV1::Shared => AccountSaleData::Shared,
+                AccountSaleDataV1::Lottery(lottery_sale_data) => AccountSaleData::Lottery(LotteryAccountSaleData {
+                    eligible_tickets: lottery_sale_data.eligible_tickets,
+                    deposit_tickets: lottery_sale_data.deposit_tickets,
+                    ticket_ids: lottery_sale_data.ticket_ids,
+                    win_ticket_ids: lottery_sale_data.win_ticket_ids,
+                    tier: Tier::default(),
+                }),
+            },
+        }
+    }
+}
+
+/// The layout of a project account before it was wrapped by UpgradableProjectAccount, it's stored as UpgradableProjectAccount::V1 after the migration.
+/// A lottery account is stored as the current version instead, see UpgradableProjectAccount::from_v1.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ProjectAccountV1 {
+    pub sale_data: Option<AccountSaleV1>,
+    pub distribution_data: Option<AccountDistributionV1>,
+}
+
+impl UpgradableProjectAccount {
+    /// The old layout doesn't store the tier that weights the lottery tickets, so the tier of a lottery account is
+    /// derived from its eligible tickets and the account is stored as the current version.
+    pub(crate) fn from_v1(project_account: ProjectAccountV1, tier_configs: &TierConfigsType) -> Self {
+        let tier = match &project_account.sale_data {
+            Some(AccountSaleV1 { sale_data: AccountSaleDataV1::Lottery(lottery_sale_data), .. }) =>
+                Some(get_tier_from_eligible_tickets(tier_configs, lottery_sale_data.eligible_tickets)),
+            _ => None,
+        };
+
+        match tier {
+            Some(tier) => {
+                let mut project_account = ProjectAccount::from(project_account);
+                if let Some(AccountSale { sale_data: AccountSaleData::Lottery(lottery_sale_data), .. }) = &mut project_account.sale_data {
+                    lottery_sale_data.tier = tier;
+                }
+                UpgradableProjectAccount::from(project_account)
+            },
+            None => UpgradableProjectAccount::V1(project_account),
+        }
+    }
+}
+
+/// The tier with the most tickets that the eligible tickets cover, the lower tier if some tiers give the same tickets.
+pub(crate) fn get_tier_from_eligible_tickets(tier_configs: &TierConfigsType, eligible_tickets: TicketNumber) -> Tier {
+    tier_configs.iter()
+        .filter(|(_, tier_config)| tier_config.ticket <= eligible_tickets)
+        .max_by(|(tier_1, tier_config_1), (tier_2, tier_config_2)| tier_config_1.ticket.cmp(&tier_config_2.ticket).then(tier_2.partial_cmp(tier_1).unwrap()))
+        .map_or(Tier::default(), |(tier, _)| *tier)
+}
+
+impl From<ProjectAccountV1> for ProjectAccount {
+    fn from(project_account: ProjectAccountV1) -> Self {
+        Self {
+            sale_data: project_account.sale_data.map(AccountSale::from),
+            distribution_data: project_account.distribution_data.map(AccountDistribution::from),
+        }
+    }
+}
+
+/// The layout of a tier config before the lottery weights were supported.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct TierConfigV1 {
+    pub min_point: Balance,
+    pub ticket: TicketNumber,
+    pub allocation: AllocationNumber,
+}
+
+/// The layout of the config before KYC providers were supported.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ConfigV1 {
+    pub tier_configs: HashMap<Tier, TierConfigV1>,
+}
+
+impl From<ConfigV1> for Config {
+    fn from(config: ConfigV1) -> Self {
+        Self {
+            tier_configs: config.tier_configs.into_iter()
+                .map(|(tier, tier_config)| (tier, TierConfig::new(tier_config.min_point, tier_config.ticket, tier_config.allocation)))
+                .collect(),
+            kyc_provider_public_keys: vec![],
+            burn_account_id: None,
+            blacklist_policy: BlacklistPolicy::default(),
+            staking_token_id: None,
+        }
+    }
+}
+
+/// The layout of the contract state before projects and project accounts were versioned.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct OldIDOContract {
+    pub owner_id: AccountId,
+    pub staking_contract_id: AccountId,
+    pub funding_ft_token_ids: UnorderedSet<AccountId>,
+    pub projects: UnorderedMap<ProjectId, ProjectV1>,
+    pub accounts_by_project: LookupMap<ProjectId, UnorderedMap<AccountId, ProjectAccountV1>>,
+    pub tickets_by_project: LookupMap<ProjectId, TicketAndAccountLookupMap>,
+    pub projects_by_account: LookupMap<AccountId, ProjectIdUnorderedSet>,
+    pub test_mode_enabled: bool,
+    pub config: ConfigV1,
+}
+
+#[near_bindgen]
+impl IDOContract {
+    /// Upgrade the contract state from the previous layout.
+    /// All projects are re-written as their V1 version, they're converted to the current version when they're read.
+    /// The project accounts are re-written by migrate_accounts in many calls, so a project with many accounts fits in the gas limit.
+    /// The contract stays paused until all accounts are migrated.
+    /// The collections keep their storage prefixes.
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let mut old_contract: OldIDOContract = env::state_read().expect("Old state doesn't exist.");
+
+        let old_projects: Vec<(ProjectId, ProjectV1)> = old_contract.projects.to_vec();
+        old_contract.projects.clear();
+
+        let config = Config::from(old_contract.config);
+        let mut projects: UnorderedMap<ProjectId, UpgradableProject> = UnorderedMap::new(get_storage_key(StorageKey::ProjectKey));
+        let mut account_migrations: UnorderedMap<ProjectId, u64> = UnorderedMap::new(get_storage_key(StorageKey::AccountMigrationsKey));
+
+        for (project_id, project) in old_projects {
+            projects.insert(&project_id, &UpgradableProject::V1(project));
+
+            if old_contract.accounts_by_project.get(&project_id).map_or(false, |old_accounts| !old_accounts.is_empty()) {
+                account_migrations.insert(&project_id, &0);
+            }
+        }
+
+        env::log(format!("Migrated {} project(s), the accounts of {} project(s) are left to migrate.", projects.len(), account_migrations.len()).as_bytes());
+
+        if !old_contract.test_mode_enabled {
+            clear_time_offset();
+        }
+
+        Self {
+            owner_id: old_contract.owner_id,
+            staking_contract_id: old_contract.staking_contract_id,
+            funding_ft_token_ids: old_contract.funding_ft_token_ids,
+            projects,
+            accounts_by_project: LookupMap::new(get_storage_key(StorageKey::AccountsByProjectKey)),
+            tickets_by_project: old_contract.tickets_by_project,
+            projects_by_account: old_contract.projects_by_account,
+            test_mode_enabled: old_contract.test_mode_enabled,
+            config,
+            storage_accounts: LookupMap::new(get_storage_key(StorageKey::StorageAccountKey)),
+            admin_ids: UnorderedSet::new(get_storage_key(StorageKey::AdminKey)),
+            paused: !account_migrations.is_empty(),
+            project_stats: LookupMap::new(get_storage_key(StorageKey::ProjectStatsKey)),
+            claim_delegates: LookupMap::new(get_storage_key(StorageKey::ClaimDelegatesKey)),
+            vesting_tokens: UnorderedMap::new(get_storage_key(StorageKey::VestingTokensKey)),
+            blacklist: UnorderedSet::new(get_storage_key(StorageKey::BlacklistKey)),
+            project_blacklists: LookupMap::new(get_storage_key(StorageKey::ProjectBlacklistsKey)),
+            account_migrations,
+        }
+    }
+
+    /// Re-write the next accounts of a project from the old layout, a lottery account gets its tier here.
+    /// The migrated count of each project is stored, so every account is migrated once. Returns the number of accounts that are left.
+    pub fn migrate_accounts(&mut self, project_id: ProjectId, limit: u64) -> U64 {
+        self.assert_owner();
+        let from_index = self.account_migrations.get(&project_id).expect("The accounts of the project are already migrated.");
+
+        let old_accounts_by_project: LookupMap<ProjectId, UnorderedMap<AccountId, ProjectAccountV1>> = LookupMap::new(get_storage_key(StorageKey::AccountsByProjectKey));
+        let old_accounts = old_accounts_by_project.get(&project_id).expect("No accounts found");
+        let old_account_ids = old_accounts.keys_as_vector();
+        let old_project_accounts = old_accounts.values_as_vector();
+        let to_index = std::cmp::min(from_index + limit, old_accounts.len());
+
+        let mut accounts_in_project = self.internal_get_accounts_by_project_or_panic(project_id);
+        for index in from_index..to_index {
+            let project_account = UpgradableProjectAccount::from_v1(old_project_accounts.get(index).unwrap(), &self.config.tier_configs);
+            accounts_in_project.insert(&old_account_ids.get(index).unwrap(), &project_account);
+        }
+        self.accounts_by_project.insert(&project_id, &accounts_in_project);
+
+        if to_index == old_accounts.len() {
+            self.account_migrations.remove(&project_id);
+        } else {
+            self.account_migrations.insert(&project_id, &to_index);
+        }
+        env::log(format!("Migrated {} account(s) of project {}, {} account(s) are left.", to_index - from_index, project_id, old_accounts.len() - to_index).as_bytes());
+
+        U64(old_accounts.len() - to_index)
+    }
+
+    /// The projects whose accounts are waiting for migrate_accounts.
+    pub fn get_account_migrations(&self) -> Vec<ProjectId> {
+        self.account_migrations.keys().collect()
+    }
+}
@@ -3,4 +3,16 @@ use crate::*;
 pub mod testing;
 pub mod project;
 pub mod account;
-pub mod tier;
\ No newline at end of file
+pub mod tier;
+pub mod ticket;
+pub mod storage;
+pub mod batch_auction;
+pub mod round;
+pub mod kyc;
+pub mod fund;
+pub mod stats;
+pub mod lottery;
+pub mod claim;
+pub mod vesting;
+pub mod leftover;
+pub mod blacklist;
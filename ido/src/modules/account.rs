@@ -1,6 +1,7 @@
 use crate::*;
+use crate::migration::ProjectAccountV1;
 
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Debug, Default)]
 pub struct LotteryAccountSaleData {
     /// The number of eligible ticket. Ex: 10
     pub eligible_tickets: TicketNumber,
@@ -10,12 +11,29 @@ pub struct LotteryAccountSaleData {
     pub ticket_ids: Vec<TicketNumber>,
     /// The list of win tickets
     pub win_ticket_ids: Vec<TicketNumber>,
+    /// The staking tier that the eligible tickets come from, it weights the tickets in the lottery draw.
+    pub tier: Tier,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct BatchAuctionAccountSaleData {
+    /// The hex encoded sha256 of "{rate_numberator}:{token_amount}:{salt}"
+    pub bid_hash: Option<String>,
+    /// The max price that the account pays for a token, the denominator is the one of token_sale_rate.
+    pub bid_rate_numberator: u64,
+    /// The number of tokens that the account wants to buy.
+    pub bid_token_amount: Balance,
+    pub revealed: bool,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq)]
 pub enum AccountSaleData {
     Shared,
     Lottery(LotteryAccountSaleData),
+    DutchAuction,
+    Overflow,
+    Fcfs,
+    BatchAuction(BatchAuctionAccountSaleData),
 }
 
 impl Default for AccountSaleData {
@@ -35,16 +53,25 @@ pub struct AccountSaleJson {
     pub committed_amount: U128,
     // This property is used for AccountSaleData::Lottery only.
     pub lottery_sale_data: Option<LotteryAccountSaleData>,
+    // This property is used for AccountSaleData::BatchAuction only.
+    pub batch_auction_sale_data: Option<BatchAuctionAccountSaleData>,
 }
 
 impl AccountSaleJson {
     pub fn from(account_sale: AccountSale) -> Self {
+        let (lottery_sale_data, batch_auction_sale_data) = match account_sale.sale_data {
+            AccountSaleData::Shared => (None, None),
+            AccountSaleData::Lottery(data) => (Some(data), None),
+            AccountSaleData::DutchAuction => (None, None),
+            AccountSaleData::Overflow => (None, None),
+            AccountSaleData::Fcfs => (None, None),
+            AccountSaleData::BatchAuction(data) => (None, Some(data)),
+        };
+
         Self {
             committed_amount: U128::from(account_sale.committed_amount),
-            lottery_sale_data: match account_sale.sale_data {
-                AccountSaleData::Shared => None,
-                AccountSaleData::Lottery(data) => Some(data),
-            },
+            lottery_sale_data,
+            batch_auction_sale_data,
         }
     }
 }
@@ -54,6 +81,11 @@ pub struct AccountDistribution {
     pub unlocked_amount: Balance,
     pub locked_amount: Balance,
     pub claimed_amount: Balance,
+    /// The fund that is returned to the account after the sale. Ex: the difference between the bid and the clearing price.
+    pub refund_amount: Balance,
+    pub refunded_amount: Balance,
+    /// The fund that the account committed in the leftover sale.
+    pub leftover_committed_amount: Balance,
 }
 
 #[derive(Serialize, Deserialize,Debug)]
@@ -61,6 +93,9 @@ pub struct AccountDistributionJson {
     pub unlocked_amount: U128,
     pub locked_amount: U128,
     pub claimed_amount: U128,
+    pub refund_amount: U128,
+    pub refunded_amount: U128,
+    pub leftover_committed_amount: U128,
 }
 
 impl AccountDistributionJson {
@@ -69,10 +104,35 @@ impl AccountDistributionJson {
             unlocked_amount: U128::from(account_distribution.unlocked_amount),
             locked_amount: U128::from(account_distribution.locked_amount),
             claimed_amount: U128::from(account_distribution.claimed_amount),
+            refund_amount: U128::from(account_distribution.refund_amount),
+            refunded_amount: U128::from(account_distribution.refunded_amount),
+            leftover_committed_amount: U128::from(account_distribution.leftover_committed_amount),
         }
     }
 }
 
+/// The stored versions of a project account, an old version is converted to the current version when it's read.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum UpgradableProjectAccount {
+    V1(ProjectAccountV1),
+    Current(ProjectAccount),
+}
+
+impl From<UpgradableProjectAccount> for ProjectAccount {
+    fn from(project_account: UpgradableProjectAccount) -> Self {
+        match project_account {
+            UpgradableProjectAccount::V1(project_account) => ProjectAccount::from(project_account),
+            UpgradableProjectAccount::Current(project_account) => project_account,
+        }
+    }
+}
+
+impl From<ProjectAccount> for UpgradableProjectAccount {
+    fn from(project_account: ProjectAccount) -> Self {
+        UpgradableProjectAccount::Current(project_account)
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Default)]
 pub struct ProjectAccount {
     pub sale_data: Option<AccountSale>,
@@ -0,0 +1,152 @@
+use crate::*;
+
+/// The hex encoded sha256 of "{rate_numberator}:{token_amount}:{salt}", it must match the submitted bid hash.
+pub(crate) fn get_bid_hash(rate_numberator: u64, token_amount: Balance, salt: &str) -> String {
+    to_hex(&env::sha256(format!("{}:{}:{}", rate_numberator, token_amount, salt).as_bytes()))
+}
+
+impl IDOContract {
+    /// The whole deposit is kept as the escrow of the sealed bid, the unused fund is refunded at distribution.
+    pub(crate) fn internal_commit_batch_auction_project(&mut self,
+        project_id: ProjectId,
+        account_id: &AccountId,
+        deposit: u128,
+    ) -> Balance {
+            let initial_storage_usage = env::storage_usage();
+            let mut project_account = self.internal_get_account_by_project_or_panic(project_id, &account_id);
+            let mut project = self.internal_get_project_or_panic(project_id);
+
+            let mut account_sale = project_account.sale_data.unwrap_or(AccountSale {
+                committed_amount: 0,
+                sale_data: AccountSaleData::BatchAuction(BatchAuctionAccountSaleData::default()),
+            });
+            account_sale.committed_amount += deposit;
+
+            // Update Project account and Project total_fund_committed
+            project_account.sale_data = Some(account_sale);
+            project.total_fund_committed += deposit;
+
+            self.internal_save_account_by_project(project_id, account_id, project_account);
+            self.internal_save_project(project_id, project);
+
+            // The account pays for the storage of its sale data
+            self.internal_update_storage_usage(account_id, initial_storage_usage);
+
+            deposit
+    }
+
+    pub(crate) fn internal_submit_bid_hash(&mut self, project_id: ProjectId, account_id: &AccountId, bid_hash: String) {
+        let project = self.internal_get_project_or_panic(project_id);
+        assert!(matches!(project.sale_type, SaleType::BatchAuction { .. }), "The project is not a batch auction.");
+        assert!(self.is_whitelist(project_id, account_id.clone()), "Account does not register whitelisting this project");
+        project.assert_sale_period();
+        self.assert_not_paused(&project);
+
+        let initial_storage_usage = env::storage_usage();
+        let mut project_account = self.internal_get_account_by_project_or_panic(project_id, account_id);
+        let mut account_sale = project_account.sale_data.unwrap_or(AccountSale {
+            committed_amount: 0,
+            sale_data: AccountSaleData::BatchAuction(BatchAuctionAccountSaleData::default()),
+        });
+
+        // The bid can be replaced until the sales period ends
+        account_sale.sale_data = AccountSaleData::BatchAuction(BatchAuctionAccountSaleData {
+            bid_hash: Some(bid_hash),
+            ..BatchAuctionAccountSaleData::default()
+        });
+        project_account.sale_data = Some(account_sale);
+
+        self.internal_save_account_by_project(project_id, account_id, project_account);
+        self.internal_update_storage_usage(account_id, initial_storage_usage);
+    }
+
+    pub(crate) fn internal_reveal_bid(&mut self, project_id: ProjectId, account_id: &AccountId, rate_numberator: u64, token_amount: Balance, salt: String) {
+        let project = self.internal_get_project_or_panic(project_id);
+        assert!(project.is_in_reveal_period(), "The project isn't in reveal period.");
+        self.assert_not_paused(&project);
+        assert!(rate_numberator >= project.token_sale_rate.numberator, "The bid price is less than the reserve price.");
+
+        let mut project_account = self.internal_get_account_by_project_or_panic(project_id, account_id);
+        let mut account_sale = project_account.sale_data.expect("The account has no bid.");
+
+        let mut bid = match account_sale.sale_data {
+            AccountSaleData::BatchAuction(bid) => bid,
+            _ => panic!("Invalid sale_data"),
+        };
+        assert!(!bid.revealed, "The bid was revealed.");
+        assert_eq!(bid.bid_hash, Some(get_bid_hash(rate_numberator, token_amount, &salt)), "The bid doesn't match the bid hash.");
+
+        let bid_rate = Rate::new(rate_numberator, project.token_sale_rate.denominator);
+        assert!(project.get_fund_amount(&bid_rate, token_amount) <= account_sale.committed_amount, "The committed fund doesn't cover the bid.");
+
+        bid.bid_rate_numberator = rate_numberator;
+        bid.bid_token_amount = token_amount;
+        bid.revealed = true;
+        account_sale.sale_data = AccountSaleData::BatchAuction(bid);
+        project_account.sale_data = Some(account_sale);
+
+        self.internal_save_account_by_project(project_id, account_id, project_account);
+    }
+
+    /// Fill the revealed bids from the highest price until token_raised_amount is sold,
+    /// the price of the last filled bid is the clearing price. Ties are filled in the order of the accounts.
+    /// Return the clearing rate, it's the reserve price if the auction is undersubscribed.
+    pub(crate) fn internal_distribute_batch_auction(&mut self, project_id: ProjectId, project: &Project) -> Rate {
+        let mut accounts_by_project = self.internal_get_accounts_by_project_or_panic(project_id);
+
+        let mut bids: Vec<(AccountId, u64, Balance)> = accounts_by_project.iter()
+            .filter_map(|(account_id, project_account)| match ProjectAccount::from(project_account).sale_data {
+                Some(AccountSale { sale_data: AccountSaleData::BatchAuction(bid), .. }) if bid.revealed =>
+                    Some((account_id, bid.bid_rate_numberator, bid.bid_token_amount)),
+                _ => None,
+            })
+            .collect();
+        bids.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let mut clearing_rate = project.token_sale_rate;
+        let mut remaining_tokens = project.token_raised_amount;
+        let mut filled_token_amounts: HashMap<AccountId, Balance> = HashMap::new();
+        for (account_id, rate_numberator, token_amount) in bids {
+            if remaining_tokens == 0 {
+                break;
+            }
+
+            let filled_token_amount = std::cmp::min(token_amount, remaining_tokens);
+            remaining_tokens -= filled_token_amount;
+            filled_token_amounts.insert(account_id, filled_token_amount);
+
+            if remaining_tokens == 0 {
+                clearing_rate = Rate::new(rate_numberator, project.token_sale_rate.denominator);
+            }
+        }
+
+        let accounts: Vec<(AccountId, ProjectAccount)> = accounts_by_project.iter()
+            .map(|(account_id, project_account)| (account_id, ProjectAccount::from(project_account)))
+            .collect();
+        for (account_id, project_account) in accounts {
+            if let Some(account_sale) = project_account.sale_data {
+                if account_sale.committed_amount != 0 {
+                    // The unfilled and the unrevealed bids are fully refunded
+                    let token_amount = filled_token_amounts.get(&account_id).cloned().unwrap_or(0);
+                    let account_distribution = AccountDistribution {
+                        unlocked_amount: token_amount,
+                        locked_amount: 0,
+                        claimed_amount: 0,
+                        refund_amount: account_sale.committed_amount - project.get_fund_amount(&clearing_rate, token_amount),
+                        refunded_amount: 0,
+                        leftover_committed_amount: 0,
+                    };
+                    let new_project_account = ProjectAccount {
+                        sale_data: Some(account_sale),
+                        distribution_data: Some(account_distribution),
+                    };
+                    accounts_by_project.insert(&account_id, &UpgradableProjectAccount::from(new_project_account));
+                }
+            }
+        }
+
+        env::log(format!("Project {} is settled at the clearing rate of {}", project_id, clearing_rate.get_rate()).as_bytes());
+
+        clearing_rate
+    }
+}
@@ -0,0 +1,77 @@
+use crate::*;
+
+/// What a blacklisted account can't do.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum BlacklistPolicy {
+    /// The account can't register the whitelist or commit, but it can claim what it already bought.
+    BlockParticipation,
+    /// The account can't register the whitelist, commit, claim or get refunds.
+    BlockParticipationAndClaims,
+}
+
+impl Default for BlacklistPolicy {
+    fn default() -> Self { BlacklistPolicy::BlockParticipationAndClaims }
+}
+
+impl IDOContract {
+    fn internal_get_project_blacklist(&self, project_id: ProjectId) -> UnorderedSet<AccountId> {
+        self.project_blacklists.get(&project_id)
+            .unwrap_or_else(|| UnorderedSet::new(get_storage_key(StorageKey::ProjectBlacklistInnerKey(project_id))))
+    }
+
+    /// The account is added to the blacklist of the project, or the contract-wide blacklist if the project is not set.
+    pub(crate) fn internal_add_to_blacklist(&mut self, account_id: &AccountId, project_id: Option<ProjectId>) {
+        if let Some(project_id) = project_id {
+            self.assert_project_exist(project_id);
+            let mut project_blacklist = self.internal_get_project_blacklist(project_id);
+            project_blacklist.insert(account_id);
+            self.project_blacklists.insert(&project_id, &project_blacklist);
+        } else {
+            self.blacklist.insert(account_id);
+        }
+        env::log(format!("Blacklist: account_id={}, project_id={:?}", account_id, project_id).as_bytes());
+    }
+
+    pub(crate) fn internal_remove_from_blacklist(&mut self, account_id: &AccountId, project_id: Option<ProjectId>) {
+        if let Some(project_id) = project_id {
+            self.internal_get_project_or_panic(project_id);
+            let mut project_blacklist = self.internal_get_project_blacklist(project_id);
+            project_blacklist.remove(account_id);
+            self.project_blacklists.insert(&project_id, &project_blacklist);
+        } else {
+            self.blacklist.remove(account_id);
+        }
+    }
+
+    pub(crate) fn internal_get_blacklist(&self, project_id: Option<ProjectId>) -> Vec<AccountId> {
+        match project_id {
+            Some(project_id) => self.internal_get_project_blacklist(project_id).to_vec(),
+            None => self.blacklist.to_vec(),
+        }
+    }
+
+    /// An account is blacklisted in a round if it's blacklisted in the main project.
+    pub(crate) fn internal_is_blacklisted(&self, project_id: ProjectId, account_id: &AccountId) -> bool {
+        if self.blacklist.contains(account_id) {
+            return true;
+        }
+
+        let project = self.internal_get_project_or_panic(project_id);
+        std::iter::once(project_id).chain(project.parent_id.into_iter())
+            .any(|id| self.project_blacklists.get(&id).map_or(false, |project_blacklist| project_blacklist.contains(account_id)))
+    }
+
+    pub(crate) fn assert_not_blacklisted(&self, project_id: ProjectId, account_id: &AccountId) {
+        assert!(!self.internal_is_blacklisted(project_id, account_id), "The account is blacklisted.");
+    }
+
+    /// The claims of a blacklisted account are only blocked by the BlockParticipationAndClaims policy.
+    pub(crate) fn can_claim(&self, project_id: ProjectId, account_id: &AccountId) -> bool {
+        self.config.blacklist_policy == BlacklistPolicy::BlockParticipation || !self.internal_is_blacklisted(project_id, account_id)
+    }
+
+    pub(crate) fn assert_can_claim(&self, project_id: ProjectId, account_id: &AccountId) {
+        assert!(self.can_claim(project_id, account_id), "The account is blacklisted.");
+    }
+}
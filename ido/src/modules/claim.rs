@@ -0,0 +1,43 @@
+use crate::*;
+
+/// The maximum number of claim delegates of an account, it bounds the storage that is charged to the account.
+pub const MAX_CLAIM_DELEGATES: usize = 10;
+
+impl IDOContract {
+    pub(crate) fn internal_get_claim_delegates(&self, account_id: &AccountId) -> Vec<AccountId> {
+        self.claim_delegates.get(account_id).unwrap_or_default()
+    }
+
+    /// The storage of the delegates is charged to the account.
+    pub(crate) fn internal_add_claim_delegate(&mut self, account_id: &AccountId, delegate_id: AccountId) {
+        self.assert_storage_registered(account_id);
+        assert_ne!(account_id, &delegate_id, "The account can't be its own claim delegate.");
+
+        let initial_storage_usage = env::storage_usage();
+        let mut delegate_ids = self.internal_get_claim_delegates(account_id);
+        assert!(!delegate_ids.contains(&delegate_id), "The account is already a claim delegate.");
+        assert!(delegate_ids.len() < MAX_CLAIM_DELEGATES, "The account has reached the maximum number of claim delegates.");
+
+        delegate_ids.push(delegate_id);
+        self.claim_delegates.insert(account_id, &delegate_ids);
+        self.internal_update_storage_usage(account_id, initial_storage_usage);
+    }
+
+    pub(crate) fn internal_remove_claim_delegate(&mut self, account_id: &AccountId, delegate_id: &AccountId) {
+        let initial_storage_usage = env::storage_usage();
+        let mut delegate_ids = self.internal_get_claim_delegates(account_id);
+        assert!(delegate_ids.contains(delegate_id), "The account isn't a claim delegate.");
+
+        delegate_ids.retain(|id| id != delegate_id);
+        if delegate_ids.is_empty() {
+            self.claim_delegates.remove(account_id);
+        } else {
+            self.claim_delegates.insert(account_id, &delegate_ids);
+        }
+        self.internal_update_storage_usage(account_id, initial_storage_usage);
+    }
+
+    pub(crate) fn assert_claim_delegate(&self, account_id: &AccountId, delegate_id: &AccountId) {
+        assert!(self.internal_get_claim_delegates(account_id).contains(delegate_id), "The account isn't a claim delegate.");
+    }
+}
@@ -0,0 +1,52 @@
+use crate::*;
+
+/// The asset that the fund of a project is committed in.
+#[derive(PartialEq, Debug)]
+pub enum FundAsset {
+    Near,
+    FungibleToken(AccountId),
+}
+
+impl FundAsset {
+    /// Transfer the fund from the contract to an account, the memo is only sent with a fungible token.
+    pub(crate) fn transfer(&self, receiver_id: AccountId, amount: Balance, memo: String) -> Promise {
+        match self {
+            FundAsset::Near => Promise::new(receiver_id).transfer(amount),
+            FundAsset::FungibleToken(fund_contract_id) => ext_ft_contract::ft_transfer(
+                receiver_id,
+                U128(amount),
+                Some(memo),
+                fund_contract_id,
+                DEPOSIT_ONE_YOCTOR,
+                FT_TRANSFER_GAS
+            ),
+        }
+    }
+}
+
+impl Project {
+    /// The fund is NEAR when the fund_contract_id is empty.
+    pub(crate) fn get_fund_asset(&self) -> FundAsset {
+        if self.fund_contract_id == "" {
+            FundAsset::Near
+        } else {
+            FundAsset::FungibleToken(self.fund_contract_id.clone())
+        }
+    }
+
+    pub(crate) fn accepts_fund_asset(&self, fund_asset: &FundAsset) -> bool {
+        self.get_fund_asset() == *fund_asset
+    }
+}
+
+impl IDOContract {
+    /// Commit the fund of an account to a project and return the amount that isn't accepted.
+    pub(crate) fn internal_commit_fund(&mut self, project_id: ProjectId, account_id: &AccountId, fund_asset: FundAsset, amount: Balance) -> Balance {
+        let project = self.internal_get_project_or_panic(project_id);
+        assert!(project.accepts_fund_asset(&fund_asset), "The project doesn't accept the fund asset.");
+
+        let committed = self.internal_commit(project_id, account_id, amount);
+
+        amount - committed
+    }
+}
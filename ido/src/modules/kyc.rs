@@ -0,0 +1,52 @@
+use std::convert::TryFrom;
+
+use ed25519_dalek::{Signature, Verifier};
+use near_sdk::json_types::Base64VecU8;
+
+use crate::*;
+
+/// The first byte of a near PublicKey is the curve type.
+const ED25519_CURVE_TYPE: u8 = 0;
+const ED25519_PUBLIC_KEY_LENGTH: usize = 33;
+
+/// A KYC provider signs "{account_id}:{project_id}:{expiry}" with one of the provider keys.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct KycAttestation {
+    /// The timestamp in nanoseconds after that the attestation can't be used
+    pub expiry: U64,
+    pub signature: Base64VecU8,
+}
+
+pub(crate) fn get_kyc_message(account_id: &AccountId, project_id: ProjectId, expiry: Timestamp) -> String {
+    format!("{}:{}:{}", account_id, project_id, expiry)
+}
+
+impl IDOContract {
+    pub(crate) fn internal_add_kyc_provider_key(&mut self, public_key: PublicKey) {
+        assert!(public_key.len() == ED25519_PUBLIC_KEY_LENGTH && public_key[0] == ED25519_CURVE_TYPE, "Only ed25519 public keys are supported.");
+
+        if !self.config.kyc_provider_public_keys.contains(&public_key) {
+            self.config.kyc_provider_public_keys.push(public_key);
+        }
+    }
+
+    pub(crate) fn internal_remove_kyc_provider_key(&mut self, public_key: PublicKey) {
+        self.config.kyc_provider_public_keys.retain(|provider_key| *provider_key != public_key);
+    }
+
+    pub(crate) fn assert_kyc_attestation(&self, account_id: &AccountId, project_id: ProjectId, kyc_attestation: Option<KycAttestation>) {
+        let kyc_attestation = kyc_attestation.expect("The project requires a KYC attestation.");
+        assert!(get_current_time() < kyc_attestation.expiry.0, "The KYC attestation is expired.");
+
+        let message = get_kyc_message(account_id, project_id, kyc_attestation.expiry.0);
+        let signature = Signature::try_from(&kyc_attestation.signature.0[..]).expect("The KYC signature is invalid.");
+
+        let verified = self.config.kyc_provider_public_keys.iter().any(|public_key| {
+            ed25519_dalek::PublicKey::from_bytes(&public_key[1..])
+                .map(|public_key| public_key.verify(message.as_bytes(), &signature).is_ok())
+                .unwrap_or(false)
+        });
+        assert!(verified, "The KYC attestation is not signed by a KYC provider.");
+    }
+}
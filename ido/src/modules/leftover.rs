@@ -0,0 +1,111 @@
+use crate::*;
+
+/// The accounts that can buy in the leftover sale.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LeftoverEligibility {
+    /// All whitelisted accounts of the project.
+    Whitelist,
+    /// Only the accounts that didn't win any ticket of the lottery.
+    LotteryLosers,
+}
+
+/// A second chance sale of the unsold tokens at the token sale rate after the main sale is distributed.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeftoverSale {
+    pub eligibility: LeftoverEligibility,
+    /// The max fund that an account can commit in the leftover sale.
+    pub max_allocation_per_user: u128,
+    pub end_date: Timestamp,
+    /// The fund that is committed in the leftover sale.
+    #[serde(default)]
+    pub total_fund_committed: Balance,
+}
+
+impl Project {
+    /// The leftover sale starts after the distribution if it's configured and there are unsold tokens.
+    pub(crate) fn get_status_after_distribution(&self) -> ProjectStatus {
+        if self.leftover_sale.is_some() && self.unsold_token_amount > 0 {
+            ProjectStatus::LeftoverSales
+        } else {
+            ProjectStatus::Distribution
+        }
+    }
+}
+
+impl IDOContract {
+    pub(crate) fn internal_set_project_leftover_sale(&mut self, project_id: ProjectId, leftover_sale: Option<LeftoverSale>) {
+        let mut project = self.internal_get_project_or_panic(project_id);
+        assert!(get_current_time() <= project.sale_end_date, "The leftover sale must be configured before the sale ends.");
+
+        if let Some(leftover_sale) = &leftover_sale {
+            assert!(project.sale_end_date < leftover_sale.end_date, "The leftover sale must end after the sale.");
+            assert!(leftover_sale.max_allocation_per_user > 0, "The max allocation of the leftover sale must be greater than 0.");
+            if leftover_sale.eligibility == LeftoverEligibility::LotteryLosers {
+                assert!(matches!(project.sale_type, SaleType::Lottery { .. }), "The project isn't a lottery.");
+            }
+        }
+
+        project.leftover_sale = leftover_sale.map(|leftover_sale| LeftoverSale { total_fund_committed: 0, ..leftover_sale });
+        self.internal_save_project(project_id, project);
+    }
+
+    /// Buy the unsold tokens at the token sale rate, the tokens are distributed immediately.
+    /// The fund is added to the total fund of the project, so it's withdrawn by the project owner with the main sale.
+    pub(crate) fn internal_commit_leftover(&mut self, project_id: ProjectId, account_id: &AccountId, amount: Balance) -> Balance {
+        let initial_storage_usage = env::storage_usage();
+        let mut project = self.internal_get_project_or_panic(project_id);
+        let mut leftover_sale = project.leftover_sale.clone().expect("The project has no leftover sale.");
+        assert!(get_current_time() <= leftover_sale.end_date, "The leftover sale is over.");
+
+        let mut project_account = self.internal_get_account_by_project_or_panic(project_id, account_id);
+        if leftover_sale.eligibility == LeftoverEligibility::LotteryLosers {
+            let is_lottery_loser = matches!(&project_account.sale_data,
+                Some(AccountSale { sale_data: AccountSaleData::Lottery(lottery_sale_data), .. }) if lottery_sale_data.win_ticket_ids.is_empty());
+            assert!(is_lottery_loser, "Only the lottery losers can buy in the leftover sale.");
+        }
+
+        let is_first_commit = project_account.sale_data.as_ref().map_or(true, |account_sale| account_sale.committed_amount == 0)
+            && project_account.distribution_data.as_ref().map_or(true, |account_distribution| account_distribution.leftover_committed_amount == 0);
+        let mut account_distribution = project_account.distribution_data.unwrap_or_default();
+        let remaining_allocation = leftover_sale.max_allocation_per_user.saturating_sub(account_distribution.leftover_committed_amount);
+        assert!(remaining_allocation > 0, "The account has reached the max allocation of the leftover sale.");
+        let remaining_fund = project.get_fund_amount(&project.token_sale_rate, project.unsold_token_amount);
+        assert!(remaining_fund > 0, "The leftover tokens are sold out.");
+
+        // Only the fund of whole token units is committed
+        let token_amount = project.get_token_amount(&project.token_sale_rate, std::cmp::min(amount, std::cmp::min(remaining_allocation, remaining_fund)));
+        let committed = project.get_fund_amount(&project.token_sale_rate, token_amount);
+
+        let (unlocked_amount, locked_amount) = project.split_vested_amount(token_amount);
+        account_distribution.unlocked_amount += unlocked_amount;
+        account_distribution.locked_amount += locked_amount;
+        account_distribution.leftover_committed_amount += committed;
+        // An account that didn't commit in the main sale buys on a first-come-first-served basis
+        project_account.sale_data = Some(project_account.sale_data.unwrap_or(AccountSale {
+            committed_amount: 0,
+            sale_data: AccountSaleData::Fcfs,
+        }));
+        project_account.distribution_data = Some(account_distribution);
+
+        project.unsold_token_amount -= token_amount;
+        project.total_fund_committed += committed;
+        leftover_sale.total_fund_committed += committed;
+        project.leftover_sale = Some(leftover_sale);
+
+        self.internal_save_account_by_project(project_id, account_id, project_account);
+        self.internal_save_project(project_id, project);
+
+        // The account pays for the storage of its sale data
+        self.internal_update_storage_usage(account_id, initial_storage_usage);
+        self.internal_update_project_stats(project_id, |project_stats| {
+            project_stats.total_distributed_amount += token_amount;
+            if is_first_commit && committed > 0 {
+                project_stats.committed_accounts += 1;
+            }
+        });
+
+        committed
+    }
+}
@@ -0,0 +1,235 @@
+use crate::*;
+
+/// The number of block seeds that are combined with the revealed secret.
+pub(crate) const LOTTERY_BLOCK_SEEDS: usize = 3;
+/// The time after the sale end date that the lottery secret can be revealed in, 1 day.
+pub const LOTTERY_REVEAL_PERIOD: Timestamp = 86_400_000_000_000;
+
+/// The inputs of a lottery draw, they're stored on the project so that anyone can recompute the win tickets.
+/// An admin commits the hash of a secret before the sale ends and reveals the secret after,
+/// then the random seeds of the reveal block and the following blocks are recorded.
+/// If the secret isn't revealed before the reveal deadline, the win tickets are drawn from the block seeds only.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LotteryDraw {
+    /// The hex encoded sha256 of the secret, it's empty if the secret was never committed.
+    pub secret_hash: String,
+    pub secret: Option<String>,
+    /// The block heights and the hex encoded random seeds of the recorded blocks.
+    pub block_seeds: Vec<(BlockHeight, String)>,
+}
+
+impl LotteryDraw {
+    /// The block seeds are only recorded without the secret after the reveal deadline.
+    pub(crate) fn is_completed(&self) -> bool {
+        self.block_seeds.len() == LOTTERY_BLOCK_SEEDS
+    }
+
+    /// The seed of the draw is the sha256 of "{secret}:{block_seed_1}:...:{block_seed_n}".
+    pub(crate) fn get_seed(&self) -> Vec<u8> {
+        let inputs: Vec<String> = self.secret.iter().cloned()
+            .chain(self.block_seeds.iter().map(|(_, block_seed)| block_seed.clone()))
+            .collect();
+
+        env::sha256(inputs.join(":").as_bytes())
+    }
+}
+
+pub(crate) fn get_lottery_secret_hash(secret: &str) -> String {
+    to_hex(&env::sha256(secret.as_bytes()))
+}
+
+/// A ticket in the lottery draw.
+#[derive(Debug)]
+pub(crate) struct LotteryTicket {
+    pub ticket_id: TicketId,
+    /// The weight in percent of a normal ticket, see TierConfig::lottery_weight.
+    pub weight: u32,
+    /// The ticket wins before the random draw.
+    pub guaranteed: bool,
+}
+
+/// A random number that only depends on the seed and the nonce.
+fn get_random_number(seed: &[u8], nonce: u64) -> u128 {
+    let hash = env::sha256(&[seed, &nonce.to_le_bytes()[..]].concat());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hash[..16]);
+
+    u128::from_le_bytes(bytes)
+}
+
+/// Draw the win tickets, the result is the same for the same seed and the same tickets.
+/// The guaranteed tickets win first in the ticket order, then the remaining tickets are drawn by weight without replacement.
+pub(crate) fn draw_win_tickets(seed: &[u8], tickets: Vec<LotteryTicket>, number_of_win_tickets: u64) -> Vec<TicketId> {
+    let (guaranteed_tickets, mut remaining_tickets): (Vec<LotteryTicket>, Vec<LotteryTicket>) = tickets.into_iter().partition(|ticket| ticket.guaranteed);
+
+    let mut win_ticket_ids: Vec<TicketId> = vec![];
+    for ticket in guaranteed_tickets {
+        if (win_ticket_ids.len() as u64) < number_of_win_tickets {
+            win_ticket_ids.push(ticket.ticket_id);
+        } else {
+            remaining_tickets.push(ticket);
+        }
+    }
+    remaining_tickets.sort_by_key(|ticket| ticket.ticket_id);
+
+    let mut total_weight: u128 = remaining_tickets.iter().map(|ticket| ticket.weight as u128).sum();
+    let mut nonce: u64 = 0;
+    while (win_ticket_ids.len() as u64) < number_of_win_tickets && total_weight > 0 {
+        let mut target = get_random_number(seed, nonce) % total_weight;
+        nonce += 1;
+
+        let index = remaining_tickets.iter()
+            .position(|ticket| {
+                if target < ticket.weight as u128 {
+                    true
+                } else {
+                    target -= ticket.weight as u128;
+                    false
+                }
+            })
+            .unwrap();
+        let ticket = remaining_tickets.remove(index);
+
+        total_weight -= ticket.weight as u128;
+        win_ticket_ids.push(ticket.ticket_id);
+    }
+
+    win_ticket_ids.sort();
+    win_ticket_ids
+}
+
+impl Project {
+    pub(crate) fn get_lottery_reveal_deadline(&self) -> Timestamp {
+        self.sale_end_date + LOTTERY_REVEAL_PERIOD
+    }
+}
+
+impl IDOContract {
+    pub(crate) fn internal_commit_lottery_secret(&mut self, project_id: ProjectId, secret_hash: String) {
+        let mut project = self.internal_get_project_or_panic(project_id);
+        assert!(matches!(project.sale_type, SaleType::Lottery { .. }), "The project isn't a lottery.");
+        assert!(get_current_time() <= project.sale_end_date, "The lottery secret must be committed before the sale ends.");
+        assert!(project.lottery_draw.is_none(), "The lottery secret is already committed.");
+
+        project.lottery_draw = Some(LotteryDraw {
+            secret_hash,
+            secret: None,
+            block_seeds: vec![],
+        });
+        self.internal_save_project(project_id, project);
+    }
+
+    /// The random seed of the reveal block is the first block seed.
+    pub(crate) fn internal_reveal_lottery_secret(&mut self, project_id: ProjectId, secret: String) {
+        let mut project = self.internal_get_project_or_panic(project_id);
+        let mut lottery_draw = project.lottery_draw.expect("The lottery secret isn't committed.");
+        assert!(project.sale_end_date < get_current_time(), "The lottery secret can't be revealed before the sale ends.");
+        assert!(get_current_time() <= project.get_lottery_reveal_deadline(), "The reveal deadline of the lottery secret has passed.");
+        assert!(lottery_draw.secret.is_none(), "The lottery secret is already revealed.");
+        assert_eq!(lottery_draw.secret_hash, get_lottery_secret_hash(&secret), "The lottery secret doesn't match the committed hash.");
+
+        lottery_draw.secret = Some(secret);
+        lottery_draw.block_seeds.push((env::block_index(), to_hex(&env::random_seed())));
+        project.lottery_draw = Some(lottery_draw);
+        self.internal_save_project(project_id, project);
+    }
+
+    /// After the reveal deadline the block seeds are recorded without the secret,
+    /// so the distribution isn't blocked if the secret is never committed or revealed.
+    pub(crate) fn internal_record_lottery_block_seed(&mut self, project_id: ProjectId) {
+        let mut project = self.internal_get_project_or_panic(project_id);
+        let is_reveal_deadline_passed = project.get_lottery_reveal_deadline() < get_current_time();
+        let mut lottery_draw = match project.lottery_draw {
+            Some(lottery_draw) => lottery_draw,
+            None => {
+                assert!(is_reveal_deadline_passed, "The lottery secret isn't committed.");
+                assert!(matches!(project.sale_type, SaleType::Lottery { .. }), "The project isn't a lottery.");
+                LotteryDraw {
+                    secret_hash: String::new(),
+                    secret: None,
+                    block_seeds: vec![],
+                }
+            }
+        };
+        assert!(lottery_draw.secret.is_some() || is_reveal_deadline_passed, "The lottery secret isn't revealed.");
+        assert!(lottery_draw.block_seeds.len() < LOTTERY_BLOCK_SEEDS, "All block seeds of the lottery are recorded.");
+        let last_block_height = lottery_draw.block_seeds.last().map_or(0, |(block_height, _)| *block_height);
+        assert!(last_block_height < env::block_index(), "The seed of this block is already recorded.");
+
+        lottery_draw.block_seeds.push((env::block_index(), to_hex(&env::random_seed())));
+        project.lottery_draw = Some(lottery_draw);
+        self.internal_save_project(project_id, project);
+    }
+
+    /// The tickets of all accounts weighted by the tier config of their staking tier.
+    /// The first ticket of an account is guaranteed if its tier guarantees a win.
+    pub(crate) fn internal_get_lottery_tickets(&self, project_id: ProjectId) -> Vec<LotteryTicket> {
+        let mut tickets: Vec<LotteryTicket> = vec![];
+
+        for (_, project_account) in self.internal_get_accounts_by_project_or_panic(project_id).iter() {
+            if let Some(AccountSale { sale_data: AccountSaleData::Lottery(lottery_sale_data), .. }) = ProjectAccount::from(project_account).sale_data {
+                let (lottery_weight, guaranteed_win) = self.config.tier_configs.get(&lottery_sale_data.tier)
+                    .map_or((DEFAULT_LOTTERY_WEIGHT, false), |tier_config| (tier_config.lottery_weight, tier_config.guaranteed_win));
+
+                for (index, ticket_id) in lottery_sale_data.ticket_ids.into_iter().enumerate() {
+                    tickets.push(LotteryTicket {
+                        ticket_id,
+                        weight: lottery_weight,
+                        guaranteed: guaranteed_win && index == 0,
+                    });
+                }
+            }
+        }
+
+        tickets.sort_by_key(|ticket| ticket.ticket_id);
+        tickets
+    }
+
+    /// Draw the win tickets from the seed and distribute the tokens of the win tickets to their accounts.
+    /// The fund of the lost tickets is refundable in the fund asset of the project.
+    pub(crate) fn internal_distribute_lottery(&mut self, project_id: ProjectId, project: &Project, allocation_per_ticket: Balance, seed: &[u8]) -> Vec<TicketId> {
+        let number_of_win_tickets = (project.get_hard_cap() / allocation_per_ticket) as u64;
+        let win_ticket_ids = draw_win_tickets(seed, self.internal_get_lottery_tickets(project_id), number_of_win_tickets);
+
+        let mut accounts_by_project = self.internal_get_accounts_by_project_or_panic(project_id);
+        let accounts: Vec<(AccountId, ProjectAccount)> = accounts_by_project.iter()
+            .map(|(account_id, project_account)| (account_id, ProjectAccount::from(project_account)))
+            .collect();
+        for (account_id, project_account) in accounts {
+            if let Some(mut account_sale) = project_account.sale_data {
+                if account_sale.committed_amount == 0 {
+                    continue;
+                }
+
+                if let AccountSaleData::Lottery(lottery_sale_data) = &mut account_sale.sale_data {
+                    lottery_sale_data.win_ticket_ids = lottery_sale_data.ticket_ids.iter()
+                        .filter(|ticket_id| win_ticket_ids.binary_search(*ticket_id).is_ok())
+                        .cloned()
+                        .collect();
+
+                    let win_fund_amount = lottery_sale_data.win_ticket_ids.len() as u128 * allocation_per_ticket;
+                    let account_distribution = AccountDistribution {
+                        unlocked_amount: project.get_token_amount(&project.token_sale_rate, win_fund_amount),
+                        locked_amount: 0,
+                        claimed_amount: 0,
+                        refund_amount: account_sale.committed_amount.saturating_sub(win_fund_amount),
+                        refunded_amount: 0,
+                        leftover_committed_amount: 0,
+                    };
+                    let new_project_account = ProjectAccount {
+                        sale_data: Some(account_sale),
+                        distribution_data: Some(account_distribution),
+                    };
+                    accounts_by_project.insert(&account_id, &UpgradableProjectAccount::from(new_project_account));
+                }
+            }
+        }
+
+        let total_win_tickets = win_ticket_ids.len() as u64;
+        self.internal_update_project_stats(project_id, |project_stats| project_stats.total_win_tickets = total_win_tickets);
+        env::log(format!("Project {} draws {} win ticket(s) from the seed {}", project_id, total_win_tickets, to_hex(seed)).as_bytes());
+
+        win_ticket_ids
+    }
+}
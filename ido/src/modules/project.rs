@@ -1,4 +1,5 @@
 use crate::*;
+use crate::migration::ProjectV1;
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum ProjectStatus {
@@ -6,6 +7,10 @@ pub enum ProjectStatus {
     Whitelist,
     Sales,
     Distribution,
+    /// The sealed bids of a batch auction are revealed after the sales period.
+    Reveal,
+    /// The unsold tokens are sold on a first-come-first-served basis after the distribution, the claims wait until it ends.
+    LeftoverSales,
 }
 
 impl Default for ProjectStatus {
@@ -29,19 +34,42 @@ pub enum SaleType {
         allocation_per_ticket: Balance,
         total_tickets: TicketNumber,
         win_ticket_ids: Option<Vec<TicketNumber>>,
-    }
+    },
+    /// The price starts at start_rate_numberator / token_sale_rate.denominator and drops after every decay_period
+    /// until it reaches the floor price (token_sale_rate) at sale_end_date.
+    /// Everyone settles at the clearing_rate which is computed when the sale ends.
+    DutchAuction {
+        start_rate_numberator: u64,
+        decay_period: Timestamp,
+        clearing_rate: Option<Rate>,
+    },
+    /// The price is fixed by token_sale_rate and there is no limit per user, the total fund can exceed the hard cap.
+    /// Everyone receives tokens pro-rata to their share and the unused fund is returned.
+    Overflow,
+    /// The price is fixed by token_sale_rate and commitments are accepted in order until the hard cap is reached,
+    /// the commitment that exceeds the hard cap or max_allocation_per_user is partially accepted.
+    Fcfs {
+        max_allocation_per_user: Balance,
+    },
+    /// Participants deposit the fund with a sealed bid during the sales period and reveal it until reveal_end_date.
+    /// Every filled bid pays the uniform clearing_rate which sells token_raised_amount, token_sale_rate is the reserve price.
+    BatchAuction {
+        reveal_end_date: Timestamp,
+        clearing_rate: Option<Rate>,
+    },
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Debug)]
 pub enum DistributionType {
     Unlocked,
+    /// The tokens are locked by the vesting schedule of the project, the position can be minted as a NEP-171 token.
     Vested
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
 pub struct Rate {
-    numberator: u64,
-    denominator: u64,
+    pub(crate) numberator: u64,
+    pub(crate) denominator: u64,
 }
 
 impl Rate {
@@ -63,6 +91,55 @@ impl Rate {
     pub(crate) fn get_rate(&self) -> f64 {
         self.numberator as f64 / self.denominator as f64
     }
+
+    /// The exact decimal string of the rate like: "0.01", a fraction that doesn't terminate is cut at MAX_RATE_FRACTION_DIGITS.
+    pub(crate) fn to_decimal_string(&self) -> String {
+        let denominator = self.denominator as u128;
+        let integer = self.numberator as u128 / denominator;
+        let mut remainder = self.numberator as u128 % denominator;
+        let mut fraction = String::new();
+
+        while remainder != 0 && fraction.len() < MAX_RATE_FRACTION_DIGITS {
+            remainder *= 10;
+            fraction.push(char::from(b'0' + (remainder / denominator) as u8));
+            remainder %= denominator;
+        }
+
+        if fraction.is_empty() {
+            integer.to_string()
+        } else {
+            format!("{}.{}", integer, fraction)
+        }
+    }
+
+    /// Rounded up so that the tokens bought at this rate never exceed the tokens for sale.
+    pub(crate) fn from_amounts(fund_amount: u128, token_amount: u128, denominator: u64) -> Self {
+        let numberator = (fund_amount * denominator as u128 + token_amount - 1) / token_amount;
+
+        Self::new(numberator as u64, denominator)
+    }
+}
+
+/// The stored versions of a project, an old version is converted to the current version when it's read.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum UpgradableProject {
+    V1(ProjectV1),
+    Current(Project),
+}
+
+impl From<UpgradableProject> for Project {
+    fn from(project: UpgradableProject) -> Self {
+        match project {
+            UpgradableProject::V1(project) => Project::from(project),
+            UpgradableProject::Current(project) => project,
+        }
+    }
+}
+
+impl From<Project> for UpgradableProject {
+    fn from(project: Project) -> Self {
+        UpgradableProject::Current(project)
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug)]
@@ -90,6 +167,37 @@ pub struct Project {
     pub whitelist_type: WhitelistType,
     pub sale_type: SaleType,
     pub distribution_type: DistributionType,
+
+    /// Whitelist registrations, commits and claims are blocked when the project is paused.
+    pub paused: bool,
+
+    /// The decimals of the sale token and the fund token, they're loaded from ft_metadata after the project is created.
+    pub token_decimals: Option<u8>,
+    pub fund_decimals: Option<u8>,
+
+    /// The main project of a round, a round shares the sale token and the fund token with its main project.
+    pub parent_id: Option<ProjectId>,
+    /// The rounds of the main project in the sale order.
+    pub round_ids: Vec<ProjectId>,
+
+    /// Only the accounts with a KYC attestation can register the whitelist.
+    pub kyc_required: bool,
+    /// The days the stake must be locked before the whitelist start date to register an XToken whitelist.
+    pub min_stake_days: u32,
+    /// The random seed of the lottery draw, the win tickets can be drawn again from it.
+    pub lottery_seed: Option<Vec<u8>>,
+    /// The committed secret and the block seeds that the lottery seed is derived from.
+    pub lottery_draw: Option<LotteryDraw>,
+    /// The release of the locked tokens of a vested project.
+    pub vesting_schedule: Option<VestingSchedule>,
+    /// The part of token_raised_amount that isn't distributed, it's computed when the project is distributed.
+    pub unsold_token_amount: Balance,
+    /// The project owner or the burn account that the unsold tokens are transferred to.
+    pub unsold_token_receiver_id: Option<AccountId>,
+    /// The second chance sale of the unsold tokens after the main sale.
+    pub leftover_sale: Option<LeftoverSale>,
+    /// The fund that the project owner has withdrawn after the distribution.
+    pub withdrawn_fund_amount: Balance,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -124,7 +232,10 @@ pub struct ProjectJson {
 
     pub token_contract_id: AccountId,
     pub token_raised_amount: U128,
-    pub token_sale_rate: f64,
+    /// The exact price of a token in the fund token like: "0.01"
+    pub token_sale_rate: String,
+    pub token_decimals: Option<u8>,
+    pub fund_decimals: Option<u8>,
 
     pub fund_contract_id: AccountId,
     pub total_fund_committed: U128,
@@ -135,11 +246,25 @@ pub struct ProjectJson {
     pub whitelist_type: WhitelistType,
     pub sale_type: SaleType,
     pub distribution_type: DistributionType,
+    pub paused: bool,
+    pub parent_id: Option<ProjectId>,
+    pub round_ids: Vec<ProjectId>,
+    pub kyc_required: bool,
+    pub min_stake_days: u32,
+    /// The hex encoded seed of the lottery draw.
+    pub lottery_seed: Option<String>,
+    pub lottery_draw: Option<LotteryDraw>,
+    pub vesting_schedule: Option<VestingSchedule>,
+    pub unsold_token_amount: U128,
+    pub unsold_token_receiver_id: Option<AccountId>,
+    /// The second chance sale of the unsold tokens after the main sale.
+    pub leftover_sale: Option<LeftoverSale>,
+    pub withdrawn_fund_amount: U128,
 }
 
 // Project functions
-impl Project {
-    pub fn from(project: ProjectInput) -> Project {
+impl From<ProjectInput> for Project {
+    fn from(project: ProjectInput) -> Self {
         Self {
             owner_id: project.owner_id.clone(),
             whitelist_start_date: project.whitelist_start_date,
@@ -158,9 +283,25 @@ impl Project {
             whitelist_type: project.whitelist_type,
             sale_type: project.sale_type,
             distribution_type: project.distribution_type,
+            paused: false,
+            token_decimals: None,
+            fund_decimals: if project.fund_contract_id == "" { Some(NEAR_DECIMALS) } else { None },
+            parent_id: None,
+            round_ids: vec![],
+            kyc_required: false,
+            lottery_seed: None,
+            min_stake_days: 0,
+            lottery_draw: None,
+            vesting_schedule: None,
+            unsold_token_amount: 0,
+            unsold_token_receiver_id: None,
+            leftover_sale: None,
+            withdrawn_fund_amount: 0,
         }
     }
+}
 
+impl Project {
     pub(crate) fn assert_whitelist_period(&self) {
         assert!(self.is_in_whitelist_period(), "Project isn't in whitelist period.");
     }
@@ -179,13 +320,82 @@ impl Project {
         self.status == ProjectStatus::Sales && self.sale_start_date <= current_time && current_time <= self.sale_end_date
     }
 
+    pub(crate) fn is_in_reveal_period(&self) -> bool {
+        let current_time = get_current_time();
+        self.status == ProjectStatus::Reveal && !self.is_after_reveal_period(current_time)
+    }
+
+    pub(crate) fn is_after_reveal_period(&self, current_time: Timestamp) -> bool {
+        match self.sale_type {
+            SaleType::BatchAuction { reveal_end_date, .. } => reveal_end_date < current_time,
+            _ => true,
+        }
+    }
+
+    /// The fund and token amounts can't be converted until the decimals of both tokens are loaded.
+    pub(crate) fn assert_decimals_loaded(&self) {
+        assert!(self.token_decimals.is_some() && self.fund_decimals.is_some(), "The token metadata of the project isn't loaded.");
+    }
+
     pub(crate) fn is_in_distribution_period(&self) -> bool {
         let current_time = get_current_time();
         self.status == ProjectStatus::Distribution && self.sale_end_date <= current_time
     }
 
+    /// The scales of the raw token amount and the raw fund amount so that both have the same decimals.
+    /// The decimals are considered equal until the metadata of both tokens is loaded.
+    fn get_decimals_scales(&self) -> (u128, u128) {
+        match (self.token_decimals, self.fund_decimals) {
+            (Some(token_decimals), Some(fund_decimals)) if fund_decimals >= token_decimals =>
+                (10u128.pow((fund_decimals - token_decimals) as u32), 1),
+            (Some(token_decimals), Some(fund_decimals)) =>
+                (1, 10u128.pow((token_decimals - fund_decimals) as u32)),
+            _ => (1, 1),
+        }
+    }
+
+    /// The raw fund amount to buy the raw token amount at the rate.
+    pub(crate) fn get_fund_amount(&self, rate: &Rate, token_amount: Balance) -> Balance {
+        let (token_scale, fund_scale) = self.get_decimals_scales();
+        rate.multiply(token_amount * token_scale) / fund_scale
+    }
+
+    /// The raw token amount that the raw fund amount buys at the rate.
+    pub(crate) fn get_token_amount(&self, rate: &Rate, fund_amount: Balance) -> Balance {
+        let (token_scale, fund_scale) = self.get_decimals_scales();
+        rate.devided_by(fund_amount * fund_scale) / token_scale
+    }
+
+    /// The rate that sells the raw token amount for the raw fund amount.
+    pub(crate) fn get_rate_from_amounts(&self, fund_amount: Balance, token_amount: Balance) -> Rate {
+        let (token_scale, fund_scale) = self.get_decimals_scales();
+        Rate::from_amounts(fund_amount * fund_scale, token_amount * token_scale, self.token_sale_rate.denominator)
+    }
+
     pub(crate) fn get_hard_cap(&self) -> Balance {
-        self.token_sale_rate.multiply(self.token_raised_amount)
+        self.get_fund_amount(&self.token_sale_rate, self.token_raised_amount)
+    }
+
+    /// The price of a dutch auction at a specific time, it drops step by step from the start price to token_sale_rate.
+    pub(crate) fn get_dutch_auction_rate(&self, start_rate_numberator: u64, decay_period: Timestamp, current_time: Timestamp) -> Rate {
+        let floor_rate = &self.token_sale_rate;
+        let total_steps = std::cmp::max(1, (self.sale_end_date - self.sale_start_date) / decay_period);
+        let elapsed_steps = std::cmp::min(total_steps, current_time.saturating_sub(self.sale_start_date) / decay_period);
+        let price_range = (start_rate_numberator - floor_rate.numberator) as u128;
+
+        let numberator = floor_rate.numberator as u128 + price_range * (total_steps - elapsed_steps) as u128 / total_steps as u128;
+
+        Rate::new(numberator as u64, floor_rate.denominator)
+    }
+
+    pub(crate) fn assert_valid_sale_type(&self) {
+        if let SaleType::DutchAuction { start_rate_numberator, decay_period, .. } = self.sale_type {
+            assert!(decay_period > 0, "The decay period of a dutch auction must be greater than 0.");
+            assert!(start_rate_numberator >= self.token_sale_rate.numberator, "The start price of a dutch auction must not be less than token_sale_rate.");
+        }
+        if let SaleType::BatchAuction { reveal_end_date, .. } = self.sale_type {
+            assert!(reveal_end_date > self.sale_end_date, "The reveal end date of a batch auction must be after sale_end_date.");
+        }
     }
 }
 
@@ -199,7 +409,11 @@ impl IDOContract {
     // Projects
 
     pub(crate) fn internal_get_project_or_panic(&self, project_id: ProjectId) -> Project {
-        self.projects.get(&project_id).expect("Project does not exist.")
+        self.projects.get(&project_id).map(Project::from).expect("Project does not exist.")
+    }
+
+    pub(crate) fn internal_save_project(&mut self, project_id: ProjectId, project: Project) {
+        self.projects.insert(&project_id, &UpgradableProject::from(project));
     }
 
     // Accounts by Project
@@ -209,11 +423,17 @@ impl IDOContract {
     }
 
     pub(crate) fn internal_get_account_by_project_or_panic(&self, project_id: ProjectId, account_id: &AccountId) -> ProjectAccount {
-        self.internal_get_accounts_by_project_or_panic(project_id).get(&account_id).expect("The account doesn't belong to the project.")
+        self.internal_get_account_by_project(project_id, account_id).expect("The account doesn't belong to the project.")
     }
 
     pub(crate) fn internal_get_account_by_project(&self, project_id: ProjectId, account_id: &AccountId) -> Option<ProjectAccount> {
-        self.internal_get_accounts_by_project_or_panic(project_id).get(&account_id)
+        self.internal_get_accounts_by_project_or_panic(project_id).get(&account_id).map(ProjectAccount::from)
+    }
+
+    pub(crate) fn internal_save_account_by_project(&mut self, project_id: ProjectId, account_id: &AccountId, project_account: ProjectAccount) {
+        let mut accounts_in_project = self.internal_get_accounts_by_project_or_panic(project_id);
+        accounts_in_project.insert(account_id, &UpgradableProjectAccount::from(project_account));
+        self.accounts_by_project.insert(&project_id, &accounts_in_project);
     }
   
     // Projects by Account
@@ -240,11 +460,13 @@ impl IDOContract {
     // Create and modify project
 
     pub(crate) fn internal_create_project(&mut self, project: Project) -> ProjectId{
+        project.assert_valid_sale_type();
+
         // Get next Id
         let project_id = self.projects.len() + 1;
 
         // Insert the project
-        self.projects.insert(&project_id, &project);
+        self.internal_save_project(project_id, project);
 
         // Insert this project to related variables, this should be done by each status
         self.accounts_by_project.insert(&project_id, &UnorderedMap::new(get_storage_key(StorageKey::AccountsByProjectInnerKey(project_id)))); 
@@ -255,6 +477,7 @@ impl IDOContract {
 
     pub(crate) fn internal_change_project_status(&mut self, project_id: ProjectId) {
         let mut project = self.internal_get_project_or_panic(project_id);
+        project.assert_decimals_loaded();
         let current_time = get_current_time();
         match project.status {
             ProjectStatus::Preparation => {
@@ -269,14 +492,27 @@ impl IDOContract {
             }
             ProjectStatus::Sales => {
                 assert!(project.sale_end_date < current_time, "Cannot change project's status to Distribution.");
+                if let SaleType::BatchAuction { .. } = project.sale_type {
+                    project.status = ProjectStatus::Reveal;
+                } else {
+                    self.internal_distribute_token_to_users(project_id, &mut project);
+                    project.status = project.get_status_after_distribution();
+                }
+            }
+            ProjectStatus::Reveal => {
+                assert!(project.is_after_reveal_period(current_time), "The reveal period is not end.");
+                self.internal_distribute_token_to_users(project_id, &mut project);
+                project.status = project.get_status_after_distribution();
+            }
+            ProjectStatus::LeftoverSales => {
+                assert!(project.leftover_sale.as_ref().map_or(true, |leftover_sale| leftover_sale.end_date < current_time), "The leftover sale is not end.");
                 project.status = ProjectStatus::Distribution;
-                self.internal_distribute_token_to_users(project_id);
             }
             _ => panic!("Unable to change project status.")
         }
 
         // Update project
-        self.projects.insert(&project_id, &project);
+        self.internal_save_project(project_id, project);
     }
 
     // Project Json
@@ -295,7 +531,9 @@ impl IDOContract {
                 
                 token_contract_id: project.token_contract_id.clone(),
                 token_raised_amount: U128(project.token_raised_amount),
-                token_sale_rate: project.token_sale_rate.get_rate(),
+                token_sale_rate: project.token_sale_rate.to_decimal_string(),
+                token_decimals: project.token_decimals,
+                fund_decimals: project.fund_decimals,
                 
                 fund_contract_id: project.fund_contract_id.clone(),
                 total_fund_committed: U128::from(project.total_fund_committed),
@@ -306,6 +544,18 @@ impl IDOContract {
                 whitelist_type: project.whitelist_type,
                 sale_type: project.sale_type,
                 distribution_type: project.distribution_type,
+                paused: project.paused,
+                parent_id: project.parent_id,
+                round_ids: project.round_ids,
+                kyc_required: project.kyc_required,
+                min_stake_days: project.min_stake_days,
+                lottery_seed: project.lottery_seed.as_ref().map(|seed| to_hex(seed)),
+                lottery_draw: project.lottery_draw,
+                vesting_schedule: project.vesting_schedule,
+                unsold_token_amount: U128(project.unsold_token_amount),
+                unsold_token_receiver_id: project.unsold_token_receiver_id,
+                leftover_sale: project.leftover_sale,
+                withdrawn_fund_amount: U128(project.withdrawn_fund_amount),
             })
         } else {
             None
@@ -322,7 +572,9 @@ impl IDOContract {
     // If project's whitelist type is fixed Xtoken, 
     // need to check user has enough xtoken or not.
     
-    pub(crate) fn internal_add_account(&mut self, account_id: &AccountId, project_id: ProjectId){
+    /// The tier is the staking tier of the account if the whitelist requires staking.
+    pub(crate) fn internal_add_account(&mut self, account_id: &AccountId, project_id: ProjectId, tier: Option<Tier>){
+        let initial_storage_usage = env::storage_usage();
 
         let mut projects_by_account = self.internal_get_projects_by_account_or_default(account_id);
 
@@ -333,19 +585,37 @@ impl IDOContract {
 
         // Insert into accounts_by_project -> Use unwrap because of making sure that it has been inserted when project created.
         let mut accounts_in_project = self.accounts_by_project.get(&project_id).unwrap();
-        accounts_in_project.insert(account_id, &ProjectAccount::default());
+        accounts_in_project.insert(account_id, &UpgradableProjectAccount::from(ProjectAccount::default()));
         self.accounts_by_project.insert(&project_id, &accounts_in_project);
+
+        // The account pays for the storage of whitelist registration
+        self.internal_update_storage_usage(account_id, initial_storage_usage);
+
+        // The contract pays for the storage of the project stats
+        self.internal_update_project_stats(project_id, |project_stats| {
+            project_stats.whitelist_accounts += 1;
+            if let Some(tier) = tier {
+                *project_stats.whitelist_accounts_by_tier.entry(tier).or_insert(0) += 1;
+            }
+        });
     }
     
-    pub fn internal_register_whitelist(&mut self, account_id: AccountId,project_id: ProjectId) {
+    pub fn internal_register_whitelist(&mut self, account_id: AccountId,project_id: ProjectId, kyc_attestation: Option<KycAttestation>) {
         let project = self.internal_get_project_or_panic(project_id);                       
         assert_eq!(project.status, ProjectStatus::Whitelist,"Project isn't on whitelist");
         assert!(project.is_in_whitelist_period(), "Project isn't on whitelist time");
+        project.assert_decimals_loaded();
+        self.assert_not_paused(&project);
+        self.assert_not_blacklisted(project_id, &account_id);
+        self.assert_storage_registered(&account_id);
+        if project.kyc_required {
+            self.assert_kyc_attestation(&account_id, project_id, kyc_attestation);
+        }
         
 
         match project.whitelist_type {
             WhitelistType::None | WhitelistType::Ticket  =>{ 
-                self.internal_add_account(&account_id, project_id);
+                self.internal_add_account(&account_id, project_id, None);
             },
             WhitelistType::XToken(xtoken) => {
                 self.internal_register_whitelist_fixed_xtoken_project(project_id, account_id, xtoken);
@@ -359,9 +629,16 @@ impl IDOContract {
         let project = self.internal_get_project_or_panic(project_id);                         
                 
         assert!(self.is_whitelist(project_id,account_id.to_string()),"Account does not register whitelisting this project");
+        self.assert_not_paused(&project);
+        project.assert_decimals_loaded();
+        self.assert_not_blacklisted(project_id, account_id);
+        if project.status == ProjectStatus::LeftoverSales {
+            return self.internal_commit_leftover(project_id, account_id, amount);
+        }
         project.assert_sale_period();
+        let is_first_commit = self.internal_get_account_by_project_or_panic(project_id, account_id).sale_data.map_or(true, |account_sale| account_sale.committed_amount == 0);
                   
-        match project.sale_type {
+        let committed = match project.sale_type {
             SaleType::Shared { 
                 min_allocation_per_user, 
                 max_allocation_per_user
@@ -383,8 +660,39 @@ impl IDOContract {
                                                             account_id,
                                                             amount,
                                                             )
+                },
+            SaleType::DutchAuction {
+                start_rate_numberator,
+                decay_period,
+                clearing_rate: _,
+            } => {
+                    self.internal_commit_dutch_auction_project(start_rate_numberator,
+                                                                decay_period,
+                                                                project_id,
+                                                                account_id,
+                                                                amount)
+                },
+            SaleType::Overflow => {
+                    self.internal_commit_overflow_project(project_id, account_id, amount)
+                },
+            SaleType::Fcfs {
+                max_allocation_per_user,
+            } => {
+                    self.internal_commit_fcfs_project(max_allocation_per_user, project_id, account_id, amount)
+                },
+            SaleType::BatchAuction {
+                reveal_end_date: _,
+                clearing_rate: _,
+            } => {
+                    self.internal_commit_batch_auction_project(project_id, account_id, amount)
                 }
+        };
+
+        if is_first_commit && committed > 0 {
+            self.internal_update_project_stats(project_id, |project_stats| project_stats.committed_accounts += 1);
         }
+
+        committed
     }
     
     pub(crate) fn internal_commit_shared_project(&mut self,
@@ -393,6 +701,7 @@ impl IDOContract {
         project_id: ProjectId, 
         account_id: &AccountId, 
         deposit: u128)-> Balance {
+            let initial_storage_usage = env::storage_usage();
             // Update Project struct
             let mut project_account_unordered_map = self.internal_get_accounts_by_project_or_panic(project_id);
             let mut project_account = self.internal_get_account_by_project_or_panic(project_id,&account_id);
@@ -420,9 +729,12 @@ impl IDOContract {
             project.total_fund_committed += deposit;
     
             // Insert new value into accounts_by_project
-            project_account_unordered_map.insert(&account_id,&project_account);
+            project_account_unordered_map.insert(&account_id,&UpgradableProjectAccount::from(project_account));
             self.accounts_by_project.insert(&project_id,&project_account_unordered_map);
-            self.projects.insert(&project_id, &project);
+            self.internal_save_project(project_id, project);
+
+            // The account pays for the storage of its sale data
+            self.internal_update_storage_usage(account_id, initial_storage_usage);
                     
             deposit              
     }
@@ -436,6 +748,7 @@ impl IDOContract {
         account_id: &AccountId,
         deposit: u128,
     ) -> Balance{
+            let initial_storage_usage = env::storage_usage();
             let mut tickets_by_project = self.internal_get_tickets_by_project_or_panic(project_id);
             let mut project_account_unordered_map = self.internal_get_accounts_by_project_or_panic(project_id);
             let mut project_account = self.internal_get_account_by_project_or_panic(project_id,&account_id);
@@ -447,7 +760,8 @@ impl IDOContract {
                         eligible_tickets: 0,
                         deposit_tickets: 0,
                         ticket_ids: vec![],
-                        win_ticket_ids: vec![]
+                        win_ticket_ids: vec![],
+                        tier: Tier::default(),
                     })
             });
                     
@@ -457,7 +771,8 @@ impl IDOContract {
                         eligible_tickets,
                         deposit_tickets,
                         ticket_ids,
-                        win_ticket_ids
+                        win_ticket_ids,
+                        tier,
                     }) => {
                         let tickets_num = (deposit / allocation_per_ticket) as u128;
                         let mut ticket_ids = ticket_ids.clone();
@@ -474,7 +789,8 @@ impl IDOContract {
                                 eligible_tickets: eligible_tickets,
                                 deposit_tickets: (deposit_tickets + tickets_num as u64),
                                 ticket_ids: ticket_ids,
-                                win_ticket_ids: win_ticket_ids
+                                win_ticket_ids: win_ticket_ids,
+                                tier: tier,
                             }  
                         );
     
@@ -495,10 +811,14 @@ impl IDOContract {
                         };
     
                         // Insert new value into accounts_by_project and tickets_by_project
-                        project_account_unordered_map.insert(&account_id,&project_account);
+                        project_account_unordered_map.insert(&account_id,&UpgradableProjectAccount::from(project_account));
                         self.accounts_by_project.insert(&project_id,&project_account_unordered_map);
                         self.tickets_by_project.insert(&project_id,&tickets_by_project);
-                        self.projects.insert(&project_id, &project);
+                        self.internal_save_project(project_id, project);
+
+                        // The account pays for the storage of its tickets
+                        self.internal_update_storage_usage(account_id, initial_storage_usage);
+                        self.internal_update_project_stats(project_id, |project_stats| project_stats.total_tickets += tickets_num as u64);
     
                         (tickets_num * allocation_per_ticket) as u128
                     }
@@ -507,10 +827,116 @@ impl IDOContract {
             }
     }
 
+    /// Accept the deposit until the total fund reaches the hard cap at the current price, the remaining is returned.
+    pub(crate) fn internal_commit_dutch_auction_project(&mut self,
+        start_rate_numberator: u64,
+        decay_period: Timestamp,
+        project_id: ProjectId,
+        account_id: &AccountId,
+        deposit: u128,
+    ) -> Balance {
+            let initial_storage_usage = env::storage_usage();
+            let mut project_account = self.internal_get_account_by_project_or_panic(project_id, &account_id);
+            let mut project = self.internal_get_project_or_panic(project_id);
+
+            let current_rate = project.get_dutch_auction_rate(start_rate_numberator, decay_period, get_current_time());
+            let current_hard_cap = project.get_fund_amount(&current_rate, project.token_raised_amount);
+            let committed = std::cmp::min(deposit, current_hard_cap.saturating_sub(project.total_fund_committed));
+            assert!(committed > 0, "The auction is sold out at the current price.");
+
+            let mut account_sale = project_account.sale_data.unwrap_or(AccountSale {
+                committed_amount: 0,
+                sale_data: AccountSaleData::DutchAuction,
+            });
+            account_sale.committed_amount += committed;
+
+            // Update Project account and Project total_fund_committed
+            project_account.sale_data = Some(account_sale);
+            project.total_fund_committed += committed;
+
+            self.internal_save_account_by_project(project_id, account_id, project_account);
+            self.internal_save_project(project_id, project);
+
+            // The account pays for the storage of its sale data
+            self.internal_update_storage_usage(account_id, initial_storage_usage);
+
+            committed
+    }
+
+    /// Accept the whole deposit, the oversubscribed fund is returned at distribution.
+    pub(crate) fn internal_commit_overflow_project(&mut self,
+        project_id: ProjectId,
+        account_id: &AccountId,
+        deposit: u128,
+    ) -> Balance {
+            let initial_storage_usage = env::storage_usage();
+            let mut project_account = self.internal_get_account_by_project_or_panic(project_id, &account_id);
+            let mut project = self.internal_get_project_or_panic(project_id);
+
+            let mut account_sale = project_account.sale_data.unwrap_or(AccountSale {
+                committed_amount: 0,
+                sale_data: AccountSaleData::Overflow,
+            });
+            account_sale.committed_amount += deposit;
+
+            // Update Project account and Project total_fund_committed
+            project_account.sale_data = Some(account_sale);
+            project.total_fund_committed += deposit;
+
+            self.internal_save_account_by_project(project_id, account_id, project_account);
+            self.internal_save_project(project_id, project);
+
+            // The account pays for the storage of its sale data
+            self.internal_update_storage_usage(account_id, initial_storage_usage);
+
+            deposit
+    }
+
+    /// Accept the deposit until the hard cap or the max allocation is reached, the remaining is returned.
+    /// The bought tokens are known at commit time so the distribution data is updated immediately.
+    pub(crate) fn internal_commit_fcfs_project(&mut self,
+        max_allocation: u128,
+        project_id: ProjectId,
+        account_id: &AccountId,
+        deposit: u128,
+    ) -> Balance {
+            let initial_storage_usage = env::storage_usage();
+            let mut project_account = self.internal_get_account_by_project_or_panic(project_id, &account_id);
+            let mut project = self.internal_get_project_or_panic(project_id);
+
+            let mut account_sale = project_account.sale_data.unwrap_or(AccountSale {
+                committed_amount: 0,
+                sale_data: AccountSaleData::Fcfs,
+            });
+
+            let remaining_hard_cap = project.get_hard_cap().saturating_sub(project.total_fund_committed);
+            assert!(remaining_hard_cap > 0, "The project is sold out.");
+            let remaining_allocation = max_allocation.saturating_sub(account_sale.committed_amount);
+            assert!(remaining_allocation > 0, "The account has reached max_allocation.");
+
+            let committed = std::cmp::min(deposit, std::cmp::min(remaining_hard_cap, remaining_allocation));
+            account_sale.committed_amount += committed;
+
+            let mut account_distribution = project_account.distribution_data.unwrap_or_default();
+            account_distribution.unlocked_amount = project.get_token_amount(&project.token_sale_rate, account_sale.committed_amount);
+
+            // Update Project account and Project total_fund_committed
+            project_account.sale_data = Some(account_sale);
+            project_account.distribution_data = Some(account_distribution);
+            project.total_fund_committed += committed;
+
+            self.internal_save_account_by_project(project_id, account_id, project_account);
+            self.internal_save_project(project_id, project);
+
+            // The account pays for the storage of its sale data
+            self.internal_update_storage_usage(account_id, initial_storage_usage);
+
+            committed
+    }
+
     // Project Distribution
-    pub(crate) fn internal_distribute_token_to_users(&mut self, project_id: ProjectId) {
+    pub(crate) fn internal_distribute_token_to_users(&mut self, project_id: ProjectId, project: &mut Project) {
         // Get project account token sales
-        let project = self.internal_get_project_or_panic(project_id);
         let project_sale_type = &project.sale_type;
         //  Traverse all account_id in ProjectAccountUnorderedMap
         let accounts_by_project_1 = self.internal_get_accounts_by_project_or_panic(project_id);
@@ -521,7 +947,7 @@ impl IDOContract {
                 max_allocation_per_user: _ 
             } => {             
                 for (account_id, project_account) in accounts_by_project_1.iter(){
-                    
+                    let project_account = ProjectAccount::from(project_account);
                     let account_sale = project_account.sale_data.unwrap_or(
                         AccountSale{
                         committed_amount: 0,
@@ -530,16 +956,23 @@ impl IDOContract {
                         
                     if account_sale.committed_amount != 0 {
                         //  Create AccountDistribution data for all account
+                        // The tokens are shared pro-rata, an account never gets more than its fund buys at the sale rate
+                        let token_amount = std::cmp::min(
+                            mul_div(account_sale.committed_amount, project.token_raised_amount, project.total_fund_committed),
+                            project.get_token_amount(&project.token_sale_rate, account_sale.committed_amount));
                         let account_distribution = AccountDistribution {
-                            unlocked_amount: (account_sale.committed_amount / project.total_fund_committed) * project.token_raised_amount,
+                            unlocked_amount: token_amount,
                             locked_amount: 0,
-                            claimed_amount: 0
+                            claimed_amount: 0,
+                            refund_amount: 0,
+                            refunded_amount: 0,
+                            leftover_committed_amount: 0,
                         };
                         let new_project_account = ProjectAccount {
                             sale_data: Some(account_sale),
                             distribution_data: Some(account_distribution)
                         };
-                        accounts_by_project_2.insert(&account_id, &new_project_account);
+                        accounts_by_project_2.insert(&account_id, &UpgradableProjectAccount::from(new_project_account));
                     }
                 }
             },
@@ -547,42 +980,129 @@ impl IDOContract {
             SaleType::Lottery { 
                 allocation_per_ticket, 
                 total_tickets, 
-                win_ticket_ids
+                win_ticket_ids: _
             } =>{
-                let tickets_by_project = self.internal_get_tickets_by_project_or_panic(project_id);
-                // TODO: Random win_ticket and save to win_ticket_ids
-                // Blocktimestamp of near is nanoseconds so 4 last numbers of timestamp will be randomly
-                // Ex: if the 4 last numbers is 1234. We do 123 * 234 * 1234 = 35516988. 
-                // So we have 35, 51, 69, 88 is 4 numbers of last 2digit in user ticket.
-                // Check through all user ticket by tickets_by_project.
-                // Ex: If total tickets is 12055. 
-                //     But there're only 1_000_000(token_raised_amount) / 1_000 (allocation_per_ticket) = 1000 win tickets
-                // 
+                let allocation_per_ticket = *allocation_per_ticket;
+                let total_tickets = *total_tickets;
+                // The seed is stored so that the draw can be reproduced
+                let seed = project.lottery_draw.as_ref()
+                    .filter(|lottery_draw| lottery_draw.is_completed())
+                    .expect("The lottery draw isn't completed.")
+                    .get_seed();
+                let win_ticket_ids = self.internal_distribute_lottery(project_id, project, allocation_per_ticket, &seed);
+
+                project.lottery_seed = Some(seed);
+                project.sale_type = SaleType::Lottery {
+                    allocation_per_ticket,
+                    total_tickets,
+                    win_ticket_ids: Some(win_ticket_ids),
+                };
+            }
+
+            SaleType::DutchAuction {
+                start_rate_numberator,
+                decay_period,
+                clearing_rate: _,
+            } => {
+                // The clearing price is the floor price unless the total fund buys more than the tokens for sale at the floor price.
+                let clearing_rate = if project.total_fund_committed > project.get_hard_cap() {
+                    project.get_rate_from_amounts(project.total_fund_committed, project.token_raised_amount)
+                } else {
+                    project.token_sale_rate
+                };
+
                 for (account_id, project_account) in accounts_by_project_1.iter(){
-                    //  Create AccountDistribution data for all account
-                    let account_sale = project_account.sale_data.unwrap_or(
-                        AccountSale{
-                        committed_amount: 0,
-                        sale_data: AccountSaleData::Lottery(
-                            LotteryAccountSaleData{
-                                eligible_tickets: 0,
-                                deposit_tickets: 0,
-                                ticket_ids: vec![],
-                                win_ticket_ids: vec![]
-                            })
-                    });
+                    let project_account = ProjectAccount::from(project_account);
 
-                    if account_sale.committed_amount != 0 {
-                        //  TODO: Create AccountDistribution data for all account
+                    if let Some(account_sale) = project_account.sale_data {
+                        if account_sale.committed_amount != 0 {
+                            // Settle the account at the clearing price, the difference is refundable
+                            let token_amount = project.get_token_amount(&clearing_rate, account_sale.committed_amount);
+                            let account_distribution = AccountDistribution {
+                                unlocked_amount: token_amount,
+                                locked_amount: 0,
+                                claimed_amount: 0,
+                                refund_amount: account_sale.committed_amount - project.get_fund_amount(&clearing_rate, token_amount),
+                                refunded_amount: 0,
+                                leftover_committed_amount: 0,
+                            };
+                            let new_project_account = ProjectAccount {
+                                sale_data: Some(account_sale),
+                                distribution_data: Some(account_distribution)
+                            };
+                            accounts_by_project_2.insert(&account_id, &UpgradableProjectAccount::from(new_project_account));
+                        }
                     }
                 }
 
-                self.projects.insert(&project_id, &project);
+                env::log(format!("Project {} is settled at the clearing rate of {}", project_id, clearing_rate.get_rate()).as_bytes());
+
+                project.sale_type = SaleType::DutchAuction {
+                    start_rate_numberator: *start_rate_numberator,
+                    decay_period: *decay_period,
+                    clearing_rate: Some(clearing_rate),
+                };
+            }
+
+            SaleType::Overflow => {
+                let is_oversubscribed = project.total_fund_committed > project.get_hard_cap();
+
+                for (account_id, project_account) in accounts_by_project_1.iter(){
+                    let project_account = ProjectAccount::from(project_account);
+
+                    if let Some(account_sale) = project_account.sale_data {
+                        if account_sale.committed_amount != 0 {
+                            // The tokens are shared pro-rata when the sale is oversubscribed, the unused fund is refundable
+                            let token_amount = if is_oversubscribed {
+                                mul_div(account_sale.committed_amount, project.token_raised_amount, project.total_fund_committed)
+                            } else {
+                                project.get_token_amount(&project.token_sale_rate, account_sale.committed_amount)
+                            };
+                            let account_distribution = AccountDistribution {
+                                unlocked_amount: token_amount,
+                                locked_amount: 0,
+                                claimed_amount: 0,
+                                refund_amount: account_sale.committed_amount - project.get_fund_amount(&project.token_sale_rate, token_amount),
+                                refunded_amount: 0,
+                                leftover_committed_amount: 0,
+                            };
+                            let new_project_account = ProjectAccount {
+                                sale_data: Some(account_sale),
+                                distribution_data: Some(account_distribution)
+                            };
+                            accounts_by_project_2.insert(&account_id, &UpgradableProjectAccount::from(new_project_account));
+                        }
+                    }
+                }
+            }
+
+            SaleType::Fcfs {
+                max_allocation_per_user: _,
+            } => {
+                // The distribution data was created at commit time
+            }
+
+            SaleType::BatchAuction {
+                reveal_end_date,
+                clearing_rate: _,
+            } => {
+                let reveal_end_date = *reveal_end_date;
+                let clearing_rate = self.internal_distribute_batch_auction(project_id, project);
+
+                project.sale_type = SaleType::BatchAuction {
+                    reveal_end_date,
+                    clearing_rate: Some(clearing_rate),
+                };
             }
         }
         
         //  Insert into accounts_by_project
         self.accounts_by_project.insert(&project_id, &accounts_by_project_2);
+        self.internal_vest_distributions(project_id, project);
+        self.internal_update_distribution_stats(project_id);
+
+        let total_distributed_amount = self.internal_get_project_stats_or_default(project_id).total_distributed_amount;
+        project.unsold_token_amount = project.token_raised_amount.saturating_sub(total_distributed_amount);
     }
 
 }
\ No newline at end of file
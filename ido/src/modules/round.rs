@@ -0,0 +1,77 @@
+use crate::*;
+
+/// A sale round of a project, the sale token and the fund token are the ones of the main project.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectRoundInput {
+    pub whitelist_start_date: Timestamp,
+    pub whitelist_end_date: Timestamp,
+    pub sale_start_date: Timestamp,
+    pub sale_end_date: Timestamp,
+
+    pub token_raised_amount: U128,
+    pub token_sale_rate_numberator: u64,
+    pub token_sale_rate_denominator: u64,
+
+    pub whitelist_type: WhitelistType,
+    pub sale_type: SaleType,
+    pub distribution_type: DistributionType,
+}
+
+impl IDOContract {
+    /// A round is stored as a project so that the whitelist, commits, tickets and distribution are tracked per round.
+    pub(crate) fn internal_add_project_round(&mut self, project_id: ProjectId, round: ProjectRoundInput) -> ProjectId {
+        let mut project = self.internal_get_project_or_panic(project_id);
+        assert!(project.parent_id.is_none(), "A round can't own rounds.");
+
+        let last_round_id = project.round_ids.last().cloned().unwrap_or(project_id);
+        let last_round = self.internal_get_project_or_panic(last_round_id);
+        assert!(last_round.sale_end_date <= round.sale_start_date, "The round must start after the sales period of the previous round.");
+
+        let round_id = self.internal_create_project(Project {
+            owner_id: project.owner_id.clone(),
+            whitelist_start_date: round.whitelist_start_date,
+            whitelist_end_date: round.whitelist_end_date,
+            sale_start_date: round.sale_start_date,
+            sale_end_date: round.sale_end_date,
+            token_contract_id: project.token_contract_id.clone(),
+            token_raised_amount: round.token_raised_amount.0,
+            token_sale_rate: Rate::new(round.token_sale_rate_numberator, round.token_sale_rate_denominator),
+            fund_contract_id: project.fund_contract_id.clone(),
+            total_fund_committed: 0,
+            status: ProjectStatus::Preparation,
+            whitelist_type: round.whitelist_type,
+            sale_type: round.sale_type,
+            distribution_type: round.distribution_type,
+            paused: false,
+            token_decimals: project.token_decimals,
+            fund_decimals: project.fund_decimals,
+            parent_id: Some(project_id),
+            round_ids: vec![],
+            kyc_required: project.kyc_required,
+            lottery_seed: None,
+            lottery_draw: None,
+            min_stake_days: project.min_stake_days,
+            vesting_schedule: None,
+            unsold_token_amount: 0,
+            unsold_token_receiver_id: None,
+            leftover_sale: None,
+            withdrawn_fund_amount: 0,
+        });
+
+        project.round_ids.push(round_id);
+        self.internal_save_project(project_id, project);
+
+        round_id
+    }
+
+    pub(crate) fn internal_get_project_rounds(&self, project_id: ProjectId) -> Vec<ProjectJson> {
+        let project = self.internal_get_project_or_panic(project_id);
+        let main_project_id = project.parent_id.unwrap_or(project_id);
+        let main_project = self.internal_get_project_or_panic(main_project_id);
+
+        std::iter::once(main_project_id)
+            .chain(main_project.round_ids.into_iter())
+            .filter_map(|round_id| self.internal_get_project(round_id, self.projects.get(&round_id).map(Project::from)))
+            .collect()
+    }
+}
@@ -0,0 +1,88 @@
+use crate::*;
+
+/// The aggregates of a project, they're updated by the whitelist, commit, distribution, claim and refund paths.
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct ProjectStats {
+    pub whitelist_accounts: u64,
+    /// Only the accounts that registered with their staking tier are counted here. Ex: an XToken project.
+    pub whitelist_accounts_by_tier: HashMap<Tier, u64>,
+    /// The number of accounts that have committed to the project.
+    pub committed_accounts: u64,
+    pub total_tickets: TicketNumber,
+    pub total_win_tickets: TicketNumber,
+    /// The sale tokens that are distributed to the accounts.
+    pub total_distributed_amount: Balance,
+    pub total_claimed_amount: Balance,
+    /// The fund that is returned to the accounts after the sale.
+    pub total_refund_amount: Balance,
+    pub total_refunded_amount: Balance,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProjectStatsJson {
+    pub project_id: ProjectId,
+    pub whitelist_accounts: u64,
+    pub whitelist_accounts_by_tier: HashMap<Tier, u64>,
+    pub committed_accounts: u64,
+    pub total_tickets: TicketNumber,
+    pub total_win_tickets: TicketNumber,
+    pub total_fund_committed: U128,
+    pub hard_cap: U128,
+    /// The committed fund over the hard cap in percent, it's over 100 when the project is oversubscribed.
+    pub fill_percentage: f64,
+    pub total_distributed_amount: U128,
+    pub total_claimed_amount: U128,
+    pub total_refund_amount: U128,
+    pub total_refunded_amount: U128,
+}
+
+impl IDOContract {
+    pub(crate) fn internal_get_project_stats_or_default(&self, project_id: ProjectId) -> ProjectStats {
+        self.project_stats.get(&project_id).unwrap_or_default()
+    }
+
+    pub(crate) fn internal_update_project_stats<F>(&mut self, project_id: ProjectId, update: F) where F: FnOnce(&mut ProjectStats) {
+        let mut project_stats = self.internal_get_project_stats_or_default(project_id);
+        update(&mut project_stats);
+        self.project_stats.insert(&project_id, &project_stats);
+    }
+
+    /// Count the distributed tokens and the refundable fund of all accounts after the project is distributed.
+    pub(crate) fn internal_update_distribution_stats(&mut self, project_id: ProjectId) {
+        let (total_distributed_amount, total_refund_amount) = self.internal_get_accounts_by_project_or_panic(project_id)
+            .iter()
+            .filter_map(|(_, project_account)| ProjectAccount::from(project_account).distribution_data)
+            .fold((0, 0), |(distributed_amount, refund_amount), account_distribution| (
+                distributed_amount + account_distribution.unlocked_amount + account_distribution.locked_amount,
+                refund_amount + account_distribution.refund_amount,
+            ));
+
+        self.internal_update_project_stats(project_id, |project_stats| {
+            project_stats.total_distributed_amount = total_distributed_amount;
+            project_stats.total_refund_amount = total_refund_amount;
+        });
+    }
+
+    pub(crate) fn internal_get_project_stats(&self, project_id: ProjectId) -> ProjectStatsJson {
+        let project = self.internal_get_project_or_panic(project_id);
+        let project_stats = self.internal_get_project_stats_or_default(project_id);
+        let hard_cap = project.get_hard_cap();
+
+        ProjectStatsJson {
+            project_id,
+            whitelist_accounts: project_stats.whitelist_accounts,
+            whitelist_accounts_by_tier: project_stats.whitelist_accounts_by_tier,
+            committed_accounts: project_stats.committed_accounts,
+            total_tickets: project_stats.total_tickets,
+            total_win_tickets: project_stats.total_win_tickets,
+            total_fund_committed: U128(project.total_fund_committed),
+            hard_cap: U128(hard_cap),
+            fill_percentage: if hard_cap == 0 { 0f64 } else { project.total_fund_committed as f64 * 100f64 / hard_cap as f64 },
+            total_distributed_amount: U128(project_stats.total_distributed_amount),
+            total_claimed_amount: U128(project_stats.total_claimed_amount),
+            total_refund_amount: U128(project_stats.total_refund_amount),
+            total_refunded_amount: U128(project_stats.total_refunded_amount),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement};
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::StorageUsage;
+
+use crate::*;
+
+/// The storage that is reserved for a registered account, it covers the StorageAccount entry of the longest account id.
+pub const STORAGE_REGISTRATION_BYTES: StorageUsage = 200;
+
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct StorageAccount {
+    /// The amount of NEAR that the account deposited to pay for its storage
+    pub deposit: Balance,
+    /// The storage that was allocated for the account: registration, whitelist, commits and tickets
+    pub used_bytes: StorageUsage,
+}
+
+impl StorageAccount {
+    pub(crate) fn get_used_balance(&self) -> Balance {
+        self.used_bytes as Balance * env::storage_byte_cost()
+    }
+
+    pub(crate) fn get_available_balance(&self) -> Balance {
+        self.deposit.saturating_sub(self.get_used_balance())
+    }
+
+    pub(crate) fn to_storage_balance(&self) -> StorageBalance {
+        StorageBalance {
+            total: U128(self.deposit),
+            available: U128(self.get_available_balance()),
+        }
+    }
+}
+
+impl IDOContract {
+    pub(crate) fn internal_get_storage_account_or_panic(&self, account_id: &AccountId) -> StorageAccount {
+        self.storage_accounts.get(account_id).expect("The account is not registered. Please deposit storage first.")
+    }
+
+    pub(crate) fn assert_storage_registered(&self, account_id: &AccountId) {
+        assert!(self.storage_accounts.get(account_id).is_some(), "The account is not registered. Please deposit storage first.");
+    }
+
+    /// Charge the storage that was changed since initial_storage_usage to the account.
+    /// Panic if the deposit of the account doesn't cover the storage.
+    pub(crate) fn internal_update_storage_usage(&mut self, account_id: &AccountId, initial_storage_usage: StorageUsage) {
+        let mut storage_account = self.internal_get_storage_account_or_panic(account_id);
+        let current_storage_usage = env::storage_usage();
+
+        if current_storage_usage >= initial_storage_usage {
+            storage_account.used_bytes += current_storage_usage - initial_storage_usage;
+        } else {
+            storage_account.used_bytes = storage_account.used_bytes.saturating_sub(initial_storage_usage - current_storage_usage);
+        }
+
+        let used_balance = storage_account.get_used_balance();
+        assert!(used_balance <= storage_account.deposit,
+            "Not enough storage deposit. Required: {} yoctoNEAR, Deposited: {} yoctoNEAR.", used_balance, storage_account.deposit);
+
+        self.storage_accounts.insert(account_id, &storage_account);
+    }
+}
+
+#[near_bindgen]
+impl StorageManagement for IDOContract {
+    #[payable]
+    fn storage_deposit(&mut self, account_id: Option<ValidAccountId>, registration_only: Option<bool>) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id: AccountId = account_id.map(|account_id| account_id.into()).unwrap_or_else(env::predecessor_account_id);
+        let registration_only = registration_only.unwrap_or(false);
+        let min_balance = self.storage_balance_bounds().min.0;
+
+        let storage_account = if let Some(mut storage_account) = self.storage_accounts.get(&account_id) {
+            if registration_only {
+                if amount > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(amount);
+                }
+            } else {
+                storage_account.deposit += amount;
+            }
+
+            storage_account
+        } else {
+            assert!(amount >= min_balance, "The attached deposit is less than the minimum storage balance ({} yoctoNEAR).", min_balance);
+
+            let deposit = if registration_only {
+                let refund = amount - min_balance;
+                if refund > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(refund);
+                }
+                min_balance
+            } else {
+                amount
+            };
+
+            StorageAccount {
+                deposit,
+                used_bytes: STORAGE_REGISTRATION_BYTES,
+            }
+        };
+
+        self.storage_accounts.insert(&account_id, &storage_account);
+
+        storage_account.to_storage_balance()
+    }
+
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let mut storage_account = self.internal_get_storage_account_or_panic(&account_id);
+        let available_balance = storage_account.get_available_balance();
+        let amount = amount.map(|amount| amount.0).unwrap_or(available_balance);
+
+        assert!(amount <= available_balance, "The amount is greater than the available storage balance ({} yoctoNEAR).", available_balance);
+
+        if amount > 0 {
+            storage_account.deposit -= amount;
+            self.storage_accounts.insert(&account_id, &storage_account);
+            Promise::new(account_id).transfer(amount);
+        }
+
+        storage_account.to_storage_balance()
+    }
+
+    /// The account can only be unregistered when it has not joined any project,
+    /// the project data of an account can't be removed so force is not supported.
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+
+        if let Some(storage_account) = self.storage_accounts.get(&account_id) {
+            let joined_projects = self.internal_get_projects_by_account_or_default(&account_id).len();
+            if joined_projects > 0 {
+                if force.unwrap_or(false) {
+                    panic!("Force unregistering an account that has joined {} project(s) is not supported.", joined_projects);
+                }
+                panic!("Cannot unregister the account that has joined {} project(s).", joined_projects);
+            }
+
+            self.storage_accounts.remove(&account_id);
+            Promise::new(account_id).transfer(storage_account.deposit);
+
+            true
+        } else {
+            env::log(format!("The account {} is not registered.", account_id).as_bytes());
+            false
+        }
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(STORAGE_REGISTRATION_BYTES as Balance * env::storage_byte_cost()),
+            max: None,
+        }
+    }
+
+    fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance> {
+        self.storage_accounts.get(account_id.as_ref()).map(|storage_account| storage_account.to_storage_balance())
+    }
+}
@@ -25,83 +25,70 @@ impl IDOContract{
         self.create_project(project);
     }
 
-    pub fn update_project_whitelist_date(&mut self, project_id: ProjectId, new_whitelist_start_date: Option<U64>, new_whitelist_end_date: Option<U64>) {
+    /// Move the contract clock to the middle of the whitelist period of a project, the project dates are not changed.
+    pub fn update_project_whitelist_date(&mut self, project_id: ProjectId) {
         self.assert_test_mode_and_owner();
 
-        let mut project = self.projects.get(&project_id).expect("No project found");
-
-        let a_half_of_whitelist_period = (project.whitelist_end_date - project.whitelist_start_date) / 2;
-        let current_time = env::block_timestamp();
+        let project = self.internal_get_project_or_panic(project_id);
+        self.internal_move_time_to(project.whitelist_start_date + (project.whitelist_end_date - project.whitelist_start_date) / 2);
+    }
 
-        println!("update_project_whitelist_date: current_time: {}, a_half_of_period: {}", current_time, a_half_of_whitelist_period);
+    /// Move the contract clock to the middle of the sale period of a project, the project dates are not changed.
+    pub fn update_project_sales_date(&mut self, project_id: ProjectId) {
+        self.assert_test_mode_and_owner();
 
-        project.whitelist_start_date = new_whitelist_start_date.unwrap_or(U64(current_time - a_half_of_whitelist_period)).0;
-        project.whitelist_end_date = new_whitelist_end_date.unwrap_or(U64(current_time + a_half_of_whitelist_period)).0;
+        let project = self.internal_get_project_or_panic(project_id);
+        self.internal_move_time_to(project.sale_start_date + (project.sale_end_date - project.sale_start_date) / 2);
+    }
 
-        self.projects.insert(&project_id, &project);
+    /// Move the contract clock past the sale end date of a project, the project dates are not changed.
+    pub fn update_project_sales_date_to_end(&mut self, project_id: ProjectId) {
+        self.assert_test_mode_and_owner();
 
+        let project = self.internal_get_project_or_panic(project_id);
+        self.internal_move_time_to(project.sale_end_date + 1);
     }
 
-    pub fn update_project_sales_date(&mut self, project_id: ProjectId) {
+    pub fn update_project_status(&mut self, project_id: ProjectId, new_status: ProjectStatus) {
         self.assert_test_mode_and_owner();
 
-        let mut project = self.projects.get(&project_id).expect("No project found");
+        let mut project = self.projects.get(&project_id).map(Project::from).expect("No project found");
+        project.status = new_status;
 
-        let a_half_of_sales_period = (project.sale_end_date - project.sale_start_date) / 2;
-        let current_time = env::block_timestamp();
-        let time_to_change: i128 = current_time as i128 - project.sale_start_date as i128 - a_half_of_sales_period as i128;
+        self.internal_save_project(project_id, project);
+    }
 
-        println!("Current time: {}", current_time);
-        println!("Sales start: {}", project.sale_start_date);
-        println!("Period: {}", a_half_of_sales_period);
-        println!("To be change: {}", time_to_change);
-        
-        project.whitelist_start_date = (project.whitelist_start_date as i128 + time_to_change) as u64;
-        project.whitelist_end_date = (project.whitelist_end_date as i128 + time_to_change) as u64;
-        project.sale_start_date = (project.sale_start_date as i128 + time_to_change) as u64;
-        project.sale_end_date = (project.sale_end_date as i128 + time_to_change) as u64;
+    /// Move the contract clock of get_current_time instead of the project dates.
+    pub fn set_time_offset(&mut self, time_offset: U64) {
+        self.assert_test_mode_and_owner();
 
-        self.projects.insert(&project_id, &project);
+        write_time_offset(time_offset.0);
+        env::log(format!("Time offset: {}, current time: {}", time_offset.0, get_current_time()).as_bytes());
     }
 
-    pub fn update_project_sales_date_to_end(&mut self, project_id: ProjectId) {
+    pub fn advance_time(&mut self, duration: U64) {
         self.assert_test_mode_and_owner();
 
-        let mut project = self.projects.get(&project_id).expect("No project found");
-        let current_timestamp = get_current_time();
-        if project.sale_end_date <= current_timestamp {
-            env::log("The sales end time is correct. No need to update.".as_bytes());
-        }
-
-        let time_period_after_sales_end = project.sale_end_date - current_timestamp + 1000;
+        write_time_offset(read_time_offset() + duration.0);
+        env::log(format!("Time offset: {}, current time: {}", read_time_offset(), get_current_time()).as_bytes());
+    }
 
-        println!("Sales end date: {}, Current time: {}, Period: {}", project.sale_end_date, current_timestamp, time_period_after_sales_end);
-        println!("Whitelist start date: {}", project.whitelist_start_date);
-        println!("Whitelist end date: {}", project.whitelist_end_date);
-        println!("Sales start date: {}", project.sale_start_date);
-        println!("Sales end date: {}", project.sale_end_date);
-        
-        project.whitelist_start_date -= time_period_after_sales_end;
-        project.whitelist_end_date -= time_period_after_sales_end;
-        project.sale_start_date -= time_period_after_sales_end;
-        project.sale_end_date -= time_period_after_sales_end;
-
-        println!("After change:"); 
-        println!("Whitelist start date: {}", project.whitelist_start_date);
-        println!("Whitelist end date: {}", project.whitelist_end_date);
-        println!("Sales start date: {}", project.sale_start_date);
-        println!("Sales end date: {}", project.sale_end_date);
-        println!("Current time: {}", current_timestamp);
-
-        self.projects.insert(&project_id, &project);
+    pub fn get_time_offset(&self) -> U64 {
+        U64(read_time_offset())
     }
 
-    pub fn update_project_status(&mut self, project_id: ProjectId, new_status: ProjectStatus) {
-        self.assert_test_mode_and_owner();
+    pub fn get_contract_time(&self) -> U64 {
+        U64(get_current_time())
+    }
+}
 
-        let mut project = self.projects.get(&project_id).expect("No project found");
-        project.status = new_status;
+impl IDOContract {
+    /// The offset can't be negative, so the contract clock can't be moved before the block time.
+    fn internal_move_time_to(&mut self, timestamp: Timestamp) {
+        let block_timestamp = env::block_timestamp();
+        assert!(block_timestamp <= timestamp, "The contract time can't be moved before the block time.");
 
-        self.projects.insert(&project_id, &project);
+        write_time_offset(timestamp - block_timestamp);
+        env::log(format!("Time offset: {}, current time: {}", read_time_offset(), get_current_time()).as_bytes());
     }
-}
\ No newline at end of file
+}
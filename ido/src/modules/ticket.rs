@@ -0,0 +1,63 @@
+use crate::*;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TicketJson {
+    pub ticket_id: TicketId,
+    pub account_id: AccountId,
+}
+
+impl IDOContract {
+    pub(crate) fn internal_get_ticket_owner(&self, project_id: ProjectId, ticket_id: TicketId) -> Option<AccountId> {
+        self.internal_get_tickets_by_project_or_panic(project_id).get(&ticket_id)
+    }
+
+    /// The ticket ids were generated in the deposit order of the account, so they're returned as it is.
+    pub(crate) fn internal_get_account_tickets(&self, project_id: ProjectId, account_id: &AccountId) -> Vec<TicketId> {
+        self.assert_project_exist(project_id);
+
+        let account = self.internal_get_account_by_project(project_id, account_id);
+        if let Some(ProjectAccount { sale_data: Some(account_sale), .. }) = account {
+            if let AccountSaleData::Lottery(lottery_sale_data) = account_sale.sale_data {
+                return lottery_sale_data.ticket_ids;
+            }
+        }
+
+        vec![]
+    }
+
+    /// Ticket ids are sequential from 0 to total_tickets - 1, so the pagination is based on the ticket id.
+    pub(crate) fn internal_get_project_tickets(&self, project_id: ProjectId, from_index: Option<u64>, limit: Option<u64>) -> Vec<TicketJson> {
+        let project = self.internal_get_project_or_panic(project_id);
+        let total_tickets = match project.sale_type {
+            SaleType::Lottery { total_tickets, .. } => total_tickets,
+            _ => return vec![],
+        };
+
+        let tickets_by_project = self.internal_get_tickets_by_project_or_panic(project_id);
+        let from_index = from_index.unwrap_or(0);
+        let to_index = std::cmp::min(total_tickets, from_index.saturating_add(limit.unwrap_or(DEFAULT_PAGE_SIZE)));
+
+        (from_index..to_index)
+            .filter_map(|ticket_id| tickets_by_project.get(&ticket_id).map(|account_id| TicketJson { ticket_id, account_id }))
+            .collect()
+    }
+
+    pub(crate) fn internal_get_win_tickets(&self, project_id: ProjectId) -> Vec<TicketJson> {
+        let project = self.internal_get_project_or_panic(project_id);
+        let win_ticket_ids = match project.sale_type {
+            SaleType::Lottery { win_ticket_ids: Some(win_ticket_ids), .. } => win_ticket_ids,
+            _ => return vec![],
+        };
+
+        let tickets_by_project = self.internal_get_tickets_by_project_or_panic(project_id);
+
+        win_ticket_ids
+            .into_iter()
+            .map(|ticket_id| TicketJson {
+                ticket_id,
+                account_id: tickets_by_project.get(&ticket_id).expect("The win ticket doesn't belong to any account."),
+            })
+            .collect()
+    }
+}
@@ -36,6 +36,16 @@ pub struct TierConfig {
     pub min_point: Balance,
     pub ticket: TicketNumber,
     pub allocation: AllocationNumber,
+    /// The weight of a ticket in the lottery draw in percent. Ex: 150 means a ticket counts 1.5x.
+    #[serde(default = "default_lottery_weight")]
+    pub lottery_weight: u32,
+    /// Every account of the tier wins at least one ticket before the random draw.
+    #[serde(default)]
+    pub guaranteed_win: bool,
+}
+
+pub(crate) fn default_lottery_weight() -> u32 {
+    DEFAULT_LOTTERY_WEIGHT
 }
 
 impl TierConfig {
@@ -44,9 +54,17 @@ impl TierConfig {
             min_point,
             ticket,
             allocation,
+            lottery_weight: DEFAULT_LOTTERY_WEIGHT,
+            guaranteed_win: false,
         }
     }
 
+    pub fn with_lottery(mut self, lottery_weight: u32, guaranteed_win: bool) -> Self {
+        self.lottery_weight = lottery_weight;
+        self.guaranteed_win = guaranteed_win;
+        self
+    }
+
     pub fn get_default_tier_configs() -> TierConfigsType {
         TierConfig::get_default_tier_configs_multiple(TOKEN_DECIMAL)
     }
@@ -0,0 +1,333 @@
+use near_contract_standards::non_fungible_token::metadata::{NFTContractMetadata, TokenMetadata, NFT_METADATA_SPEC};
+use near_contract_standards::non_fungible_token::Token;
+use near_sdk::Gas;
+use near_sdk::json_types::ValidAccountId;
+
+use crate::*;
+use crate::ft_contract::ext_self;
+
+pub const GAS_FOR_NFT_ON_TRANSFER: Gas = 25_000_000_000_000;
+pub const GAS_FOR_NFT_RESOLVE_TRANSFER: Gas = 10_000_000_000_000;
+
+pub type VestingTokenId = String;
+
+/// The locked tokens of a vested project are released linearly from the start date to the end date.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingSchedule {
+    /// The percentage of the tokens of an account that is unlocked when the project is distributed.
+    pub unlocked_percentage: u8,
+    pub start_date: Timestamp,
+    pub end_date: Timestamp,
+}
+
+/// The vesting position of an account in a project that is minted as a NEP-171 token.
+/// The owner of the token claims the tokens of the position instead of the account.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct VestingToken {
+    pub project_id: ProjectId,
+    pub account_id: AccountId,
+    pub owner_id: AccountId,
+}
+
+/// The vesting state of a position, it's the extra of the token metadata.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingStateJson {
+    pub project_id: ProjectId,
+    pub account_id: AccountId,
+    pub token_contract_id: AccountId,
+    pub unlocked_amount: U128,
+    pub locked_amount: U128,
+    pub released_amount: U128,
+    pub claimed_amount: U128,
+    pub claimable_amount: U128,
+}
+
+#[ext_contract(ext_nft_receiver)]
+pub trait NonFungibleTokenReceiver {
+    fn nft_on_transfer(&mut self, sender_id: AccountId, previous_owner_id: AccountId, token_id: VestingTokenId, msg: String) -> PromiseOrValue<bool>;
+}
+
+pub(crate) fn get_vesting_token_id(project_id: ProjectId, account_id: &AccountId) -> VestingTokenId {
+    format!("{}:{}", project_id, account_id)
+}
+
+impl Project {
+    /// The token amount of an account is split into the unlocked and the locked amount if the project is vested.
+    pub(crate) fn split_vested_amount(&self, token_amount: Balance) -> (Balance, Balance) {
+        match &self.vesting_schedule {
+            Some(vesting_schedule) if self.distribution_type == DistributionType::Vested => {
+                let unlocked_amount = token_amount * vesting_schedule.unlocked_percentage as u128 / 100;
+                (unlocked_amount, token_amount - unlocked_amount)
+            },
+            _ => (token_amount, 0),
+        }
+    }
+
+    /// The part of the locked amount that is released at the current time.
+    pub(crate) fn get_released_amount(&self, locked_amount: Balance) -> Balance {
+        let vesting_schedule = match &self.vesting_schedule {
+            Some(vesting_schedule) => vesting_schedule,
+            None => return locked_amount,
+        };
+        let current_time = get_current_time();
+        if current_time <= vesting_schedule.start_date {
+            return 0;
+        }
+        if vesting_schedule.end_date <= current_time {
+            return locked_amount;
+        }
+
+        // Split the multiplication so that it doesn't overflow
+        let elapsed = (current_time - vesting_schedule.start_date) as u128;
+        let duration = (vesting_schedule.end_date - vesting_schedule.start_date) as u128;
+        locked_amount / duration * elapsed + locked_amount % duration * elapsed / duration
+    }
+}
+
+impl IDOContract {
+    pub(crate) fn internal_set_project_vesting_schedule(&mut self, project_id: ProjectId, vesting_schedule: VestingSchedule) {
+        let mut project = self.internal_get_project_or_panic(project_id);
+        assert_eq!(project.distribution_type, DistributionType::Vested, "The project isn't vested.");
+        assert!(matches!(project.status, ProjectStatus::Preparation | ProjectStatus::Whitelist | ProjectStatus::Sales | ProjectStatus::Reveal),
+            "The vesting schedule must be set before the project is distributed.");
+        assert!(vesting_schedule.unlocked_percentage <= 100, "The unlocked percentage can't be greater than 100.");
+        assert!(vesting_schedule.start_date <= vesting_schedule.end_date, "The vesting must end after it starts.");
+
+        project.vesting_schedule = Some(vesting_schedule);
+        self.internal_save_project(project_id, project);
+    }
+
+    /// Lock the tokens of all accounts by the vesting schedule after the project is distributed.
+    /// A vested project without a schedule is distributed unlocked, ex: the projects created before vesting was supported.
+    pub(crate) fn internal_vest_distributions(&mut self, project_id: ProjectId, project: &Project) {
+        if project.distribution_type != DistributionType::Vested || project.vesting_schedule.is_none() {
+            return;
+        }
+
+        let mut accounts_by_project = self.internal_get_accounts_by_project_or_panic(project_id);
+        let accounts: Vec<(AccountId, ProjectAccount)> = accounts_by_project.iter()
+            .map(|(account_id, project_account)| (account_id, ProjectAccount::from(project_account)))
+            .collect();
+        for (account_id, mut project_account) in accounts {
+            if let Some(mut account_distribution) = project_account.distribution_data {
+                let (unlocked_amount, locked_amount) = project.split_vested_amount(account_distribution.unlocked_amount + account_distribution.locked_amount);
+                account_distribution.unlocked_amount = unlocked_amount;
+                account_distribution.locked_amount = locked_amount;
+                project_account.distribution_data = Some(account_distribution);
+                accounts_by_project.insert(&account_id, &UpgradableProjectAccount::from(project_account));
+            }
+        }
+        self.accounts_by_project.insert(&project_id, &accounts_by_project);
+    }
+
+    pub(crate) fn internal_get_vesting_token_or_panic(&self, token_id: &VestingTokenId) -> VestingToken {
+        self.vesting_tokens.get(token_id).expect("The vesting token doesn't exist.")
+    }
+
+    pub(crate) fn is_vesting_token_minted(&self, project_id: ProjectId, account_id: &AccountId) -> bool {
+        self.vesting_tokens.get(&get_vesting_token_id(project_id, account_id)).is_some()
+    }
+
+    /// The account owns the token of its position after it's minted, the storage of the token is charged to the account.
+    pub(crate) fn internal_mint_vesting_token(&mut self, project_id: ProjectId, account_id: &AccountId) -> VestingTokenId {
+        let project = self.internal_get_project_or_panic(project_id);
+        assert_eq!(project.distribution_type, DistributionType::Vested, "The project isn't vested.");
+        assert!(project.is_in_distribution_period(), "The project isn't in distribution period.");
+        self.assert_not_paused(&project);
+        self.assert_can_claim(project_id, account_id);
+
+        let token_id = get_vesting_token_id(project_id, account_id);
+        assert!(self.vesting_tokens.get(&token_id).is_none(), "The vesting token is already minted.");
+        let account_distribution = self.internal_get_account_by_project_or_panic(project_id, account_id).distribution_data
+            .expect("The account has no vesting position.");
+        assert!(account_distribution.claimed_amount < account_distribution.unlocked_amount + account_distribution.locked_amount, "The account has no vesting position.");
+
+        let initial_storage_usage = env::storage_usage();
+        self.vesting_tokens.insert(&token_id, &VestingToken {
+            project_id,
+            account_id: account_id.clone(),
+            owner_id: account_id.clone(),
+        });
+        self.internal_update_storage_usage(account_id, initial_storage_usage);
+        env::log(format!("Mint vesting token: token_id={}, owner_id={}", token_id, account_id).as_bytes());
+
+        token_id
+    }
+
+    pub(crate) fn internal_transfer_vesting_token(&mut self, sender_id: &AccountId, receiver_id: &AccountId, token_id: &VestingTokenId) -> VestingToken {
+        let mut vesting_token = self.internal_get_vesting_token_or_panic(token_id);
+        assert_eq!(&vesting_token.owner_id, sender_id, "The sender doesn't own the vesting token.");
+        assert_ne!(sender_id, receiver_id, "The sender and the receiver must be different.");
+
+        vesting_token.owner_id = receiver_id.clone();
+        self.vesting_tokens.insert(token_id, &vesting_token);
+        env::log(format!("Transfer vesting token: token_id={}, sender_id={}, receiver_id={}", token_id, sender_id, receiver_id).as_bytes());
+
+        vesting_token
+    }
+
+    pub(crate) fn internal_get_vesting_state(&self, vesting_token: &VestingToken) -> VestingStateJson {
+        let project = self.internal_get_project_or_panic(vesting_token.project_id);
+        let account_distribution = self.internal_get_account_by_project_or_panic(vesting_token.project_id, &vesting_token.account_id)
+            .distribution_data
+            .unwrap_or_default();
+
+        VestingStateJson {
+            project_id: vesting_token.project_id,
+            account_id: vesting_token.account_id.clone(),
+            token_contract_id: project.token_contract_id.clone(),
+            unlocked_amount: U128(account_distribution.unlocked_amount),
+            locked_amount: U128(account_distribution.locked_amount),
+            released_amount: U128(project.get_released_amount(account_distribution.locked_amount)),
+            claimed_amount: U128(account_distribution.claimed_amount),
+            claimable_amount: U128(self.internal_claim(vesting_token.project_id, &vesting_token.account_id)),
+        }
+    }
+}
+
+#[near_bindgen]
+impl IDOContract {
+    /// Mint the vesting position of the caller in a vested project as a NEP-171 token.
+    /// The remaining tokens of the position can only be claimed by the owner of the token with claim_vesting_token.
+    pub fn mint_vesting_token(&mut self, project_id: ProjectId) -> VestingTokenId {
+        let account_id = env::predecessor_account_id();
+        self.internal_mint_vesting_token(project_id, &account_id)
+    }
+
+    /// The owner of a vesting token claims the released tokens of the position.
+    #[payable]
+    pub fn claim_vesting_token(&mut self, token_id: VestingTokenId) -> Promise {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        let vesting_token = self.internal_get_vesting_token_or_panic(&token_id);
+        assert_eq!(vesting_token.owner_id, owner_id, "The caller doesn't own the vesting token.");
+
+        let project = self.internal_get_project_or_panic(vesting_token.project_id);
+        assert!(project.is_in_distribution_period(), "The project isn't in distribution period.");
+        self.assert_not_paused(&project);
+        self.assert_can_claim(vesting_token.project_id, &vesting_token.account_id);
+        self.assert_can_claim(vesting_token.project_id, &owner_id);
+
+        let claim_amount = self.internal_claim(vesting_token.project_id, &vesting_token.account_id);
+        assert!(claim_amount > 0, "The account has nothing to claim.");
+        let claims = vec![(vesting_token.project_id, U128(claim_amount))];
+        self.internal_record_claims(&vesting_token.account_id, &claims);
+        env::log(format!("Claim: account_id={}, receiver_id={}, amount={}", vesting_token.account_id, owner_id, claim_amount).as_bytes());
+
+        self.internal_transfer_claims(project.token_contract_id, vesting_token.account_id, owner_id, claims, None)
+    }
+
+    /// Approvals aren't supported, so only the owner can transfer the token.
+    #[payable]
+    pub fn nft_transfer(&mut self, receiver_id: ValidAccountId, token_id: VestingTokenId, approval_id: Option<u64>, memo: Option<String>) {
+        assert_one_yocto();
+        assert!(approval_id.is_none(), "Approvals aren't supported.");
+        let _ = memo;
+
+        self.internal_transfer_vesting_token(&env::predecessor_account_id(), receiver_id.as_ref(), &token_id);
+    }
+
+    #[payable]
+    pub fn nft_transfer_call(&mut self, receiver_id: ValidAccountId, token_id: VestingTokenId, approval_id: Option<u64>, memo: Option<String>, msg: String) -> Promise {
+        assert_one_yocto();
+        assert!(approval_id.is_none(), "Approvals aren't supported.");
+        let _ = memo;
+
+        let sender_id = env::predecessor_account_id();
+        let receiver_id: AccountId = receiver_id.into();
+        self.internal_transfer_vesting_token(&sender_id, &receiver_id, &token_id);
+
+        ext_nft_receiver::nft_on_transfer(
+            sender_id.clone(),
+            sender_id.clone(),
+            token_id.clone(),
+            msg,
+            &receiver_id,
+            NO_DEPOSIT,
+            GAS_FOR_NFT_ON_TRANSFER
+        ).then(
+            ext_self::nft_resolve_transfer(
+                sender_id,
+                receiver_id,
+                token_id,
+                None,
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                GAS_FOR_NFT_RESOLVE_TRANSFER
+            )
+        )
+    }
+
+    /// The token is returned to the previous owner if the receiver asks to revert the transfer,
+    /// and the receiver still owns the token. Returns true if the token stays with the receiver.
+    #[private]
+    pub fn nft_resolve_transfer(&mut self, previous_owner_id: AccountId, receiver_id: AccountId, token_id: VestingTokenId, approved_account_ids: Option<HashMap<AccountId, u64>>) -> bool {
+        assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+        let _ = approved_account_ids;
+        let must_revert = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<bool>(&value).unwrap_or(true),
+            PromiseResult::Failed => true,
+        };
+        if !must_revert {
+            return true;
+        }
+
+        match self.vesting_tokens.get(&token_id) {
+            Some(mut vesting_token) if vesting_token.owner_id == receiver_id => {
+                vesting_token.owner_id = previous_owner_id;
+                self.vesting_tokens.insert(&token_id, &vesting_token);
+                env::log(format!("Return vesting token: token_id={}, owner_id={}", token_id, vesting_token.owner_id).as_bytes());
+
+                false
+            },
+            _ => true,
+        }
+    }
+
+    /// The extra of the metadata is the JSON of the vesting state, ex: the released and the claimable amount.
+    pub fn nft_token(&self, token_id: VestingTokenId) -> Option<Token> {
+        self.vesting_tokens.get(&token_id).map(|vesting_token| {
+            let project = self.internal_get_project_or_panic(vesting_token.project_id);
+            let vesting_state = self.internal_get_vesting_state(&vesting_token);
+
+            Token {
+                token_id: token_id.clone(),
+                owner_id: vesting_token.owner_id.clone(),
+                metadata: Some(TokenMetadata {
+                    title: Some(format!("Vesting position {}", token_id)),
+                    description: Some(format!("The vested tokens of {} in project {}", vesting_token.account_id, vesting_token.project_id)),
+                    media: None,
+                    media_hash: None,
+                    copies: Some(1),
+                    issued_at: None,
+                    expires_at: None,
+                    starts_at: project.vesting_schedule.as_ref().map(|vesting_schedule| vesting_schedule.start_date.to_string()),
+                    updated_at: None,
+                    extra: Some(near_sdk::serde_json::to_string(&vesting_state).unwrap()),
+                    reference: None,
+                    reference_hash: None,
+                }),
+                approved_account_ids: Some(HashMap::new()),
+            }
+        })
+    }
+
+    pub fn nft_metadata(&self) -> NFTContractMetadata {
+        NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: "KulaPad Vesting".to_string(),
+            symbol: "KPVEST".to_string(),
+            icon: None,
+            base_uri: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+
+    pub fn get_vesting_state(&self, token_id: VestingTokenId) -> VestingStateJson {
+        self.internal_get_vesting_state(&self.internal_get_vesting_token_or_panic(&token_id))
+    }
+}
@@ -6,6 +6,7 @@ pub struct AccountJson {
     pub account_id: AccountId,
     pub locked_balance: U128,
     pub locked_days: u32,
+    pub locked_timestamp: Timestamp,
     pub unlocked_timestamp: Timestamp,
     pub staked_balance: U128,
     pub unstaked_balance: U128,
@@ -86,10 +87,27 @@ impl IDOContract {
         // Project's status must be whitelist & current time is between whitelist_start_date and whitelist_end_date
         project.assert_whitelist_period();
         
-        // TODO: Generate tickets
-        
-        // Update project
-        self.projects.insert(&project_id, &project);
+        // The eligible tickets of a lottery come from the staking tier, the tier weights the tickets in the draw
+        if let SaleType::Lottery { .. } = project.sale_type {
+            let initial_storage_usage = env::storage_usage();
+            let tier_config = self.config.tier_configs.get(&staking_account_info.tier).cloned().expect("The tier config doesn't exist.");
+            let mut project_account = self.internal_get_account_by_project_or_panic(project_id, &account_id);
+            let mut account_sale = project_account.sale_data.unwrap_or(AccountSale {
+                committed_amount: 0,
+                sale_data: AccountSaleData::Lottery(LotteryAccountSaleData::default()),
+            });
+
+            if let AccountSaleData::Lottery(lottery_sale_data) = &mut account_sale.sale_data {
+                lottery_sale_data.eligible_tickets = std::cmp::max(lottery_sale_data.deposit_tickets, tier_config.ticket);
+                lottery_sale_data.tier = staking_account_info.tier;
+            }
+
+            project_account.sale_data = Some(account_sale);
+            self.internal_save_account_by_project(project_id, &account_id, project_account);
+
+            // The account pays for the storage of its sale data
+            self.internal_update_storage_usage(&account_id, initial_storage_usage);
+        }
 
         true
     }
@@ -130,7 +148,7 @@ impl IDOContract{
             PromiseResult::Failed =>false,
             PromiseResult::Successful(result) => {
                 let account_json = near_sdk::serde_json::from_slice::<AccountJson>(&result).unwrap();
-                let registered = self.proccess_register_whitelist(account_id, project_id,account_json.point, xtoken);
+                let registered = self.proccess_register_whitelist(account_id, project_id, account_json, xtoken);
                 env::log(format!("Prepared gas: {}, Used gas: {}",env::prepaid_gas(),env::used_gas()).as_bytes());
 
                 registered
@@ -138,10 +156,21 @@ impl IDOContract{
         }
     }
 
-    pub (crate) fn proccess_register_whitelist(&mut self, account_id: AccountId, project_id: ProjectId, point: U128, xtoken: u128) -> bool{
-        env::log(format!("proccess_register_whitelist(account_id: {}, project_id: {}, point: {})", account_id, project_id,point.0).as_bytes());
-        assert!(point.0 >= xtoken,"User don't have enough XToken");
-        self.internal_add_account(&account_id, project_id);
+    pub (crate) fn proccess_register_whitelist(&mut self, account_id: AccountId, project_id: ProjectId, staking_account_info: AccountJson, xtoken: u128) -> bool{
+        env::log(format!("proccess_register_whitelist(account_id: {}, project_id: {}, point: {})", account_id, project_id, staking_account_info.point.0).as_bytes());
+        assert!(staking_account_info.point.0 >= xtoken,"User don't have enough XToken");
+
+        // The stake must be locked for min_stake_days before the whitelist starts to block last-minute stake-and-register farming
+        // locked_timestamp is the time of the first lock, a top-up of the lock doesn't move it
+        let project = self.internal_get_project_or_panic(project_id);
+        if project.min_stake_days > 0 {
+            let min_stake_duration = project.min_stake_days as u64 * ONE_DAY_IN_NANOSECONDS;
+            assert!(
+                staking_account_info.locked_timestamp > 0 && staking_account_info.locked_timestamp + min_stake_duration <= project.whitelist_start_date,
+                "The stake hasn't been locked for min_stake_days before the whitelist start date."
+            );
+        }
+        self.internal_add_account(&account_id, project_id, Some(staking_account_info.tier));
 
         true
     }
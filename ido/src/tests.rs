@@ -12,6 +12,60 @@ pub mod test_whitelist;
 #[cfg(test)]
 pub mod test_buytoken;
 
+#[cfg(test)]
+pub mod test_ticket;
+
+#[cfg(test)]
+pub mod test_migration;
+
+#[cfg(test)]
+pub mod test_storage;
+
+#[cfg(test)]
+pub mod test_pause;
+
 pub mod test_full_flow;
 
-pub mod test_staking_tier;
\ No newline at end of file
+pub mod test_staking_tier;
+#[cfg(test)]
+pub mod test_dutch_auction;
+
+#[cfg(test)]
+pub mod test_overflow;
+
+#[cfg(test)]
+pub mod test_fcfs;
+
+#[cfg(test)]
+pub mod test_batch_auction;
+
+#[cfg(test)]
+pub mod test_round;
+
+#[cfg(test)]
+pub mod test_kyc;
+
+#[cfg(test)]
+pub mod test_stake_age;
+
+#[cfg(test)]
+pub mod test_time_offset;
+
+#[cfg(test)]
+pub mod test_stats;
+
+#[cfg(test)]
+pub mod test_lottery;
+
+#[cfg(test)]
+pub mod test_claim;
+#[cfg(test)]
+pub mod test_vesting;
+#[cfg(test)]
+pub mod test_unsold_tokens;
+#[cfg(test)]
+pub mod test_leftover;
+#[cfg(test)]
+pub mod test_blacklist;
+#[cfg(test)]
+pub mod test_withdraw_fund;
\ No newline at end of file
@@ -19,7 +19,7 @@ fn test_get_project_account_info_project_exists_account_not() { // - Project exi
 
     emulator.update_context(owner.clone(), owner.clone(), 0);
     let project = get_project_1();
-    emulator.contract.create_project(project);
+    emulator.create_project(project);
 
     emulator.update_context(bob(), bob(), 0);
 
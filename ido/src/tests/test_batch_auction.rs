@@ -0,0 +1,132 @@
+use near_sdk::json_types::U128;
+
+use crate::*;
+use crate::modules::batch_auction::get_bid_hash;
+use crate::tests::test_emulator::*;
+
+const REVEAL_END_DATE: Timestamp = SALE_END_DATE + 86400000000000;
+
+fn init_batch_auction() -> (Emulator, ProjectId) {
+    let project_input = new_project_input("batch.near", "", SaleType::BatchAuction {
+        reveal_end_date: REVEAL_END_DATE,
+        clearing_rate: None,
+    });
+
+    init_sales_project(project_input, &["bob", "carol", "dave"])
+}
+
+/// Commit the fund and submit the sealed bid of an account.
+fn place_bid(emulator: &mut Emulator, project_id: ProjectId, account_id: &str, deposit: Balance, rate_numberator: u64, token_amount: Balance) {
+    emulator.set_account_id_and_desposit(account_id.to_string(), account_id.to_string(), 0);
+    emulator.contract.internal_commit(project_id, &account_id.to_string(), deposit);
+    emulator.contract.submit_bid_hash(project_id, get_bid_hash(rate_numberator, token_amount, "salt"));
+}
+
+fn reveal_bid(emulator: &mut Emulator, project_id: ProjectId, account_id: &str, rate_numberator: u64, token_amount: Balance) {
+    emulator.set_account_id_and_desposit(account_id.to_string(), account_id.to_string(), 0);
+    emulator.contract.reveal_bid(project_id, rate_numberator, U128(token_amount), "salt".to_string());
+}
+
+fn start_reveal_period(emulator: &mut Emulator, project_id: ProjectId) {
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+}
+
+fn end_batch_auction(emulator: &mut Emulator, project_id: ProjectId) {
+    emulator.set_block_timestamp(REVEAL_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+}
+
+#[test]
+fn test_batch_auction_moves_to_reveal_after_sales() {
+    let (mut emulator, project_id) = init_batch_auction();
+
+    start_reveal_period(&mut emulator, project_id);
+    assert_eq!(ProjectStatus::Reveal, emulator.contract.get_project(project_id).unwrap().status);
+
+    end_batch_auction(&mut emulator, project_id);
+    assert_eq!(ProjectStatus::Distribution, emulator.contract.get_project(project_id).unwrap().status);
+}
+
+#[test]
+fn test_batch_auction_settles_at_uniform_clearing_price() {
+    let (mut emulator, project_id) = init_batch_auction();
+    place_bid(&mut emulator, project_id, "bob", 1200, 20, 60);
+    place_bid(&mut emulator, project_id, "carol", 900, 15, 60);
+    place_bid(&mut emulator, project_id, "dave", 600, 12, 50);
+
+    start_reveal_period(&mut emulator, project_id);
+    reveal_bid(&mut emulator, project_id, "bob", 20, 60);
+    reveal_bid(&mut emulator, project_id, "carol", 15, 60);
+    reveal_bid(&mut emulator, project_id, "dave", 12, 50);
+    end_batch_auction(&mut emulator, project_id);
+
+    let project = emulator.contract.internal_get_project_or_panic(project_id);
+    if let SaleType::BatchAuction { clearing_rate: Some(clearing_rate), .. } = project.sale_type {
+        assert_eq!(15f64, clearing_rate.get_rate());
+    } else {
+        panic!("The clearing rate is not set.");
+    }
+
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(60, bob_distribution.unlocked_amount.0);
+    assert_eq!(300, bob_distribution.refund_amount.0);
+
+    // The marginal bid is partially filled
+    let carol_distribution = emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap();
+    assert_eq!(40, carol_distribution.unlocked_amount.0);
+    assert_eq!(300, carol_distribution.refund_amount.0);
+
+    let dave_distribution = emulator.contract.get_project_account_info(project_id, "dave".to_string()).distribution_data.unwrap();
+    assert_eq!(0, dave_distribution.unlocked_amount.0);
+    assert_eq!(600, dave_distribution.refund_amount.0);
+}
+
+#[test]
+fn test_batch_auction_refunds_unrevealed_bid() {
+    let (mut emulator, project_id) = init_batch_auction();
+    place_bid(&mut emulator, project_id, "bob", 1200, 20, 60);
+    place_bid(&mut emulator, project_id, "carol", 900, 15, 60);
+
+    start_reveal_period(&mut emulator, project_id);
+    reveal_bid(&mut emulator, project_id, "bob", 20, 60);
+    end_batch_auction(&mut emulator, project_id);
+
+    // Undersubscribed, the bid is filled at the reserve price
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(60, bob_distribution.unlocked_amount.0);
+    assert_eq!(600, bob_distribution.refund_amount.0);
+
+    let carol_distribution = emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap();
+    assert_eq!(0, carol_distribution.unlocked_amount.0);
+    assert_eq!(900, carol_distribution.refund_amount.0);
+}
+
+#[test]
+#[should_panic(expected = "The bid doesn't match the bid hash.")]
+fn test_reveal_bid_with_wrong_bid() {
+    let (mut emulator, project_id) = init_batch_auction();
+    place_bid(&mut emulator, project_id, "bob", 1200, 20, 60);
+
+    start_reveal_period(&mut emulator, project_id);
+    reveal_bid(&mut emulator, project_id, "bob", 20, 50);
+}
+
+#[test]
+#[should_panic(expected = "The committed fund doesn't cover the bid.")]
+fn test_reveal_bid_over_committed_fund() {
+    let (mut emulator, project_id) = init_batch_auction();
+    place_bid(&mut emulator, project_id, "bob", 1000, 20, 60);
+
+    start_reveal_period(&mut emulator, project_id);
+    reveal_bid(&mut emulator, project_id, "bob", 20, 60);
+}
+
+#[test]
+#[should_panic(expected = "The project isn't in reveal period.")]
+fn test_reveal_bid_during_sales() {
+    let (mut emulator, project_id) = init_batch_auction();
+    place_bid(&mut emulator, project_id, "bob", 1200, 20, 60);
+
+    reveal_bid(&mut emulator, project_id, "bob", 20, 60);
+}
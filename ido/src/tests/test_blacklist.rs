@@ -0,0 +1,104 @@
+use crate::*;
+use crate::modules::blacklist::BlacklistPolicy;
+use crate::tests::test_emulator::*;
+
+/// Project 1 is in the whitelist period and bob has registered the storage.
+fn init_whitelist_project() -> Emulator {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(1);
+    emulator.register_storage("bob".to_string());
+
+    emulator
+}
+
+#[test]
+fn test_manage_blacklist() {
+    let mut emulator = init_whitelist_project();
+
+    emulator.contract.add_to_blacklist("bob".to_string(), None);
+    emulator.contract.add_to_blacklist("carol".to_string(), Some(1));
+    assert_eq!(vec!["bob".to_string()], emulator.contract.get_blacklist(None));
+    assert_eq!(vec!["carol".to_string()], emulator.contract.get_blacklist(Some(1)));
+    assert!(emulator.contract.is_blacklisted(2, "bob".to_string()));
+    assert!(emulator.contract.is_blacklisted(1, "carol".to_string()));
+    assert!(!emulator.contract.is_blacklisted(2, "carol".to_string()));
+
+    emulator.contract.remove_from_blacklist("carol".to_string(), Some(1));
+    assert!(!emulator.contract.is_blacklisted(1, "carol".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Project does not exist.")]
+fn test_remove_from_blacklist_of_unknown_project() {
+    let mut emulator = init_whitelist_project();
+
+    emulator.contract.remove_from_blacklist("carol".to_string(), Some(100));
+}
+
+#[test]
+#[should_panic(expected = "You are not an admin of this contract.")]
+fn test_add_to_blacklist_by_non_admin() {
+    let mut emulator = init_whitelist_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 0);
+
+    emulator.contract.add_to_blacklist("carol".to_string(), None);
+}
+
+#[test]
+#[should_panic(expected = "The account is blacklisted.")]
+fn test_register_whitelist_when_blacklisted() {
+    let mut emulator = init_whitelist_project();
+    emulator.contract.add_to_blacklist("bob".to_string(), None);
+
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 0);
+    emulator.contract.register_whitelist(1, None);
+}
+
+#[test]
+#[should_panic(expected = "The account is blacklisted.")]
+fn test_commit_when_blacklisted_in_project() {
+    let mut emulator = init_whitelist_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 0);
+    emulator.contract.register_whitelist(1, None);
+
+    emulator.set_account_id_and_desposit("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.add_to_blacklist("bob".to_string(), Some(1));
+    emulator.contract.update_project_status(1, ProjectStatus::Sales);
+    emulator.set_block_timestamp(SALE_START_DATE);
+    emulator.contract.internal_commit(1, &"bob".to_string(), 50);
+}
+
+#[test]
+#[should_panic(expected = "The account is blacklisted.")]
+fn test_claim_when_blacklisted() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.contract.add_to_blacklist("bob".to_string(), Some(project_id));
+
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim(project_id);
+
+    assert_eq!(60, emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap().claimed_amount.0);
+}
+
+#[test]
+fn test_claim_when_blacklisted_with_block_participation_policy() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.contract.add_to_blacklist("bob".to_string(), Some(project_id));
+    emulator.contract.set_blacklist_policy(BlacklistPolicy::BlockParticipation);
+    assert_eq!(BlacklistPolicy::BlockParticipation, emulator.contract.get_blacklist_policy());
+
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim(project_id);
+
+    assert_eq!(60, emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap().claimed_amount.0);
+}
+
+#[test]
+fn test_claim_all_skips_blacklisted_projects() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.contract.add_to_blacklist("bob".to_string(), Some(project_id));
+
+    assert!(emulator.contract.internal_get_claims_by_token(&"bob".to_string(), vec![project_id]).is_empty());
+}
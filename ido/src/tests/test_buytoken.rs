@@ -2,16 +2,18 @@ use crate::tests::test_emulator::*;
 use crate::modules::account::*;
 use near_sdk::json_types::U128;
 use crate::modules::project::ProjectStatus;
+use crate::*;
 
 #[test]
 fn test_buy_token_by_near() {
     let mut emulator = Emulator::default();
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
-    emulator.contract.create_sample_projects();
+    emulator.create_sample_projects();
 
     emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
     emulator.contract.internal_change_project_status(5);
-    emulator.contract.register_whitelist(5);
+    emulator.contract.register_whitelist(5, None);
     let is_whitelist = emulator.contract.is_whitelist(5,"bob".to_string());
     assert!(is_whitelist);
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
@@ -39,11 +41,12 @@ fn test_buy_token_by_near() {
 fn test_commit_shared_project_by_ft_token() {
     let mut emulator = Emulator::default();
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
-    emulator.contract.create_sample_projects();
+    emulator.create_sample_projects();
 
     emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
     emulator.contract.internal_change_project_status(1);
-    emulator.contract.register_whitelist(1); 
+    emulator.contract.register_whitelist(1, None); 
 
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
     emulator.contract.update_project_status(1, ProjectStatus::Sales);
@@ -70,11 +73,12 @@ fn test_commit_shared_project_by_ft_token() {
 fn test_commit_lottery_project_by_ft_token(){
     let mut emulator = Emulator::default();
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
-    emulator.contract.create_sample_projects();
+    emulator.create_sample_projects();
 
     emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
     emulator.contract.internal_change_project_status(2);
-    emulator.contract.register_whitelist(2);
+    emulator.contract.register_whitelist(2, None);
 
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
     emulator.contract.update_project_status(2, ProjectStatus::Sales);
@@ -90,7 +94,8 @@ fn test_commit_lottery_project_by_ft_token(){
                 eligible_tickets: 6,
                 deposit_tickets: 2,
                 ticket_ids: vec![0,1],
-                win_ticket_ids: vec![]
+                win_ticket_ids: vec![],
+                tier: Tier::default(),
             }
         )
     };
@@ -118,7 +123,8 @@ fn test_commit_lottery_project_by_ft_token(){
                 eligible_tickets: 6,
                 deposit_tickets: 5,
                 ticket_ids: vec![0,1,2,3,4],
-                win_ticket_ids: vec![]
+                win_ticket_ids: vec![],
+                tier: Tier::default(),
             }
         )
     };
@@ -131,4 +137,60 @@ fn test_commit_lottery_project_by_ft_token(){
         lottery_project_account_sale_json.lottery_sale_data.unwrap(),
         "Lottery sale data not match ");
 
-}
\ No newline at end of file
+}
+
+/// Whitelist bob on a project and move it to the sales period.
+fn init_sample_sales_project(project_id: ProjectId) -> Emulator {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+
+    emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.internal_change_project_status(project_id);
+    emulator.contract.register_whitelist(project_id, None);
+
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.update_project_status(project_id, ProjectStatus::Sales);
+    emulator.set_block_timestamp(1651804401000000000);
+
+    emulator
+}
+
+fn get_unaccepted_amount(result: PromiseOrValue<U128>) -> u128 {
+    match result {
+        PromiseOrValue::Value(amount) => amount.0,
+        PromiseOrValue::Promise(_) => panic!("The unaccepted amount isn't returned."),
+    }
+}
+
+#[test]
+#[should_panic(expected = "The project doesn't accept the fund asset.")]
+fn test_commit_near_to_ft_project() {
+    let mut emulator = init_sample_sales_project(1);
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 25);
+
+    emulator.contract.commit(1);
+}
+
+#[test]
+fn test_ft_on_transfer_commits_fund_token() {
+    let mut emulator = init_sample_sales_project(1);
+    emulator.set_account_id_and_desposit("ft_contract".to_string(), "bob".to_string(), 0);
+
+    let unaccepted_amount = get_unaccepted_amount(emulator.contract.ft_on_transfer("bob".to_string(), U128(25), "project_id:1".to_string()));
+
+    assert_eq!(0, unaccepted_amount);
+    assert_eq!(25, emulator.contract.internal_get_project_account_info(1, "bob".to_string()).sale_data.unwrap().committed_amount.0);
+}
+
+#[test]
+fn test_ft_on_transfer_returns_other_token() {
+    let mut emulator = init_sample_sales_project(1);
+    emulator.set_account_id_and_desposit("other_ft_contract".to_string(), "bob".to_string(), 0);
+
+    let unaccepted_amount = get_unaccepted_amount(emulator.contract.ft_on_transfer("bob".to_string(), U128(25), "project_id:1".to_string()));
+
+    assert_eq!(25, unaccepted_amount);
+    assert!(emulator.contract.internal_get_project_account_info(1, "bob".to_string()).sale_data.is_none());
+}
@@ -0,0 +1,293 @@
+use std::convert::TryFrom;
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds};
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::PromiseResult;
+use near_sdk::test_utils::get_logs;
+
+use crate::*;
+use crate::tests::test_emulator::*;
+use crate::tests::test_utils::{testing_env_with_promise_results, testing_env_with_many_promise_results};
+
+fn get_valid_account_id(account_id: &str) -> ValidAccountId {
+    ValidAccountId::try_from(account_id).unwrap()
+}
+
+fn get_claimed_amount(emulator: &Emulator, project_id: ProjectId, account_id: &str) -> Balance {
+    emulator.contract.get_project_account_info(project_id, account_id.to_string()).distribution_data.unwrap().claimed_amount.0
+}
+
+fn resolve_ft_transfer(emulator: &mut Emulator, project_id: ProjectId, promise_result: PromiseResult) -> U128 {
+    let mut context = emulator.context.clone();
+    context.predecessor_account_id = context.current_account_id.clone();
+    testing_env_with_promise_results(context, promise_result);
+
+    emulator.contract.ft_transfer_callback("bob".to_string(), vec![(project_id, U128(60))])
+}
+
+/// Resolve the storage queries of a claim to the receiver, the attached deposit of the caller is kept.
+fn resolve_claim_to(emulator: &mut Emulator, project_id: ProjectId, payer_id: &str, receiver_id: &str, is_registered: bool, min_storage_deposit: Balance) {
+    let mut context = emulator.context.clone();
+    context.predecessor_account_id = context.current_account_id.clone();
+    let storage_balance = if is_registered { Some(StorageBalance { total: U128(min_storage_deposit), available: U128(0) }) } else { None };
+    let storage_balance_bounds = StorageBalanceBounds { min: U128(min_storage_deposit), max: Some(U128(min_storage_deposit)) };
+    testing_env_with_many_promise_results(context, vec![
+        PromiseResult::Successful(near_sdk::serde_json::to_vec(&storage_balance).unwrap()),
+        PromiseResult::Successful(near_sdk::serde_json::to_vec(&storage_balance_bounds).unwrap()),
+    ]);
+
+    emulator.contract.claim_to_callback(project_id, "bob".to_string(), receiver_id.to_string(), payer_id.to_string());
+}
+
+#[test]
+fn test_manage_claim_delegates() {
+    let (mut emulator, _) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+
+    emulator.contract.add_claim_delegate("cold".to_string());
+    emulator.contract.add_claim_delegate("dao".to_string());
+    assert_eq!(vec!["cold".to_string(), "dao".to_string()], emulator.contract.get_claim_delegates("bob".to_string()));
+
+    emulator.contract.remove_claim_delegate("cold".to_string());
+    assert_eq!(vec!["dao".to_string()], emulator.contract.get_claim_delegates("bob".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "The account is not registered. Please deposit storage first.")]
+fn test_add_claim_delegate_without_storage() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("carol".to_string(), "carol".to_string(), 1);
+
+    emulator.contract.add_claim_delegate("cold".to_string());
+}
+
+#[test]
+fn test_claim_to_receiver() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), FT_STORAGE_DEPOSIT);
+
+    emulator.contract.claim_to(project_id, get_valid_account_id("cold"));
+    // The claim is recorded by the callback after the storage of the receiver is known
+    assert_eq!(0, get_claimed_amount(&emulator, project_id, "bob"));
+
+    resolve_claim_to(&mut emulator, project_id, "bob", "cold", false, FT_STORAGE_DEPOSIT);
+
+    assert_eq!(vec!["Claim: account_id=bob, receiver_id=cold, amount=60".to_string()], get_logs());
+    assert_eq!(60, get_claimed_amount(&emulator, project_id, "bob"));
+    assert!(emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()).is_empty());
+}
+
+#[test]
+fn test_claim_to_registered_receiver() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), FT_STORAGE_DEPOSIT);
+    emulator.contract.claim_to(project_id, get_valid_account_id("cold"));
+
+    resolve_claim_to(&mut emulator, project_id, "bob", "cold", true, FT_STORAGE_DEPOSIT);
+
+    assert_eq!(60, get_claimed_amount(&emulator, project_id, "bob"));
+}
+
+#[test]
+fn test_claim_to_receiver_with_higher_storage_deposit() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), FT_STORAGE_DEPOSIT);
+    emulator.contract.claim_to(project_id, get_valid_account_id("cold"));
+
+    // The token requires more than the attached deposit, the deposit is returned to bob and nothing is claimed
+    resolve_claim_to(&mut emulator, project_id, "bob", "cold", false, 2 * FT_STORAGE_DEPOSIT);
+
+    assert_eq!(vec![format!("Claim failed: account_id=bob, receiver_id=cold, amount=60, storage_deposit={}", 2 * FT_STORAGE_DEPOSIT)], get_logs());
+    assert_eq!(0, get_claimed_amount(&emulator, project_id, "bob"));
+    assert_eq!(vec![(project_id, U128(60))], emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "The account has nothing to claim.")]
+fn test_claim_twice_before_the_transfer_resolves() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim(project_id);
+
+    emulator.contract.claim(project_id);
+}
+
+#[test]
+fn test_claim_is_rolled_back_when_the_transfer_fails() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim(project_id);
+
+    assert_eq!(U128(0), resolve_ft_transfer(&mut emulator, project_id, PromiseResult::Failed));
+    assert_eq!(0, get_claimed_amount(&emulator, project_id, "bob"));
+    assert_eq!(vec![(project_id, U128(60))], emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()));
+}
+
+#[test]
+fn test_claim_is_kept_when_the_transfer_succeeds() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim(project_id);
+
+    assert_eq!(U128(60), resolve_ft_transfer(&mut emulator, project_id, PromiseResult::Successful(vec![])));
+    assert_eq!(60, get_claimed_amount(&emulator, project_id, "bob"));
+    assert_eq!(U128(60), emulator.contract.get_project_stats(project_id).total_claimed_amount);
+}
+
+#[test]
+#[should_panic(expected = "Requires attached deposit of at least 1250000000000000000000 yoctoNEAR for the storage registration of the receiver.")]
+fn test_claim_to_receiver_without_storage_deposit() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+
+    emulator.contract.claim_to(project_id, get_valid_account_id("cold"));
+}
+
+#[test]
+fn test_claim_for_by_delegate() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.add_claim_delegate("dao".to_string());
+
+    emulator.set_account_id_and_desposit("dao".to_string(), "dao".to_string(), FT_STORAGE_DEPOSIT);
+    emulator.contract.claim_for(project_id, get_valid_account_id("bob"), None);
+    resolve_claim_to(&mut emulator, project_id, "dao", "dao", false, FT_STORAGE_DEPOSIT);
+
+    // The token of bob is transferred to the delegate
+    assert_eq!(vec!["Claim: account_id=bob, receiver_id=dao, amount=60".to_string()], get_logs());
+    assert_eq!(60, get_claimed_amount(&emulator, project_id, "bob"));
+}
+
+#[test]
+#[should_panic(expected = "The account isn't a claim delegate.")]
+fn test_claim_for_by_non_delegate() {
+    let (mut emulator, project_id) = init_distributed_project();
+
+    emulator.set_account_id_and_desposit("dao".to_string(), "dao".to_string(), FT_STORAGE_DEPOSIT);
+    emulator.contract.claim_for(project_id, get_valid_account_id("bob"), None);
+}
+
+#[test]
+#[should_panic(expected = "The account isn't a claim delegate.")]
+fn test_claim_for_after_delegate_removed() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.add_claim_delegate("dao".to_string());
+    emulator.contract.remove_claim_delegate("dao".to_string());
+
+    emulator.set_account_id_and_desposit("dao".to_string(), "dao".to_string(), FT_STORAGE_DEPOSIT);
+    emulator.contract.claim_for(project_id, get_valid_account_id("bob"), None);
+}
+
+#[test]
+fn test_get_claims_by_token() {
+    let (emulator, project_id) = init_distributed_project();
+
+    // The project is claimed once even if it's listed twice
+    let claims_by_token = emulator.contract.internal_get_claims_by_token(&"bob".to_string(), vec![project_id, project_id]);
+
+    assert_eq!(vec![("contract.token.near".to_string(), vec![(project_id, U128(60))])], claims_by_token);
+}
+
+#[test]
+fn test_claim_all() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+
+    emulator.contract.claim_all(vec![project_id]);
+
+    // The claims are recorded before the transfers
+    assert_eq!(60, get_claimed_amount(&emulator, project_id, "bob"));
+    assert!(emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()).is_empty());
+}
+
+#[test]
+fn test_claim_all_is_rolled_back_when_the_transfer_fails() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim_all(vec![project_id]);
+
+    assert_eq!(U128(0), resolve_ft_transfer(&mut emulator, project_id, PromiseResult::Failed));
+    assert_eq!(0, get_claimed_amount(&emulator, project_id, "bob"));
+    assert_eq!(vec![(project_id, U128(60))], emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "The account has nothing to claim.")]
+fn test_claim_all_with_nothing_to_claim() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("carol".to_string(), "carol".to_string(), 1);
+
+    emulator.contract.claim_all(vec![project_id]);
+}
+
+#[test]
+#[should_panic(expected = "Can't claim more than 10 projects at once.")]
+fn test_claim_all_too_many_projects() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+
+    emulator.contract.claim_all(vec![project_id; 11]);
+}
+
+/// The sale token of the distributed project is the staking token.
+fn init_staking_token_project() -> (Emulator, ProjectId) {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.contract.set_staking_token_id(Some("contract.token.near".to_string()));
+
+    (emulator, project_id)
+}
+
+fn resolve_claim_and_stake(emulator: &mut Emulator, project_id: ProjectId, staked_amount: Balance) -> U128 {
+    let mut context = emulator.context.clone();
+    context.predecessor_account_id = context.current_account_id.clone();
+    testing_env_with_promise_results(context, PromiseResult::Successful(format!("\"{}\"", staked_amount).into_bytes()));
+
+    emulator.contract.claim_and_stake_callback("bob".to_string(), vec![(project_id, U128(60))], U128(60))
+}
+
+#[test]
+fn test_claim_and_stake() {
+    let (mut emulator, project_id) = init_staking_token_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim_and_stake(project_id, 30);
+    assert_eq!(60, get_claimed_amount(&emulator, project_id, "bob"));
+
+    assert_eq!(U128(60), resolve_claim_and_stake(&mut emulator, project_id, 60));
+    assert_eq!(60, get_claimed_amount(&emulator, project_id, "bob"));
+    assert!(emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()).is_empty());
+}
+
+#[test]
+fn test_claim_and_stake_rejected_by_staking_pool() {
+    let (mut emulator, project_id) = init_staking_token_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim_and_stake(project_id, 30);
+
+    // The token is returned to the IDO contract, so it can be claimed again
+    assert_eq!(U128(0), resolve_claim_and_stake(&mut emulator, project_id, 0));
+    assert_eq!(0, get_claimed_amount(&emulator, project_id, "bob"));
+    assert_eq!(vec![(project_id, U128(60))], emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "The sale token of the project isn't the staking token.")]
+fn test_claim_and_stake_another_token() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.contract.set_staking_token_id(Some("staking.token.near".to_string()));
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+
+    emulator.contract.claim_and_stake(project_id, 30);
+}
+
+#[test]
+fn test_claim_and_stake_partially_staked() {
+    let (mut emulator, project_id) = init_staking_token_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim_and_stake(project_id, 30);
+    assert_eq!(60, get_claimed_amount(&emulator, project_id, "bob"));
+
+    // Only the part that the staking pool returned can be claimed again
+    assert_eq!(U128(40), resolve_claim_and_stake(&mut emulator, project_id, 40));
+    assert_eq!(40, get_claimed_amount(&emulator, project_id, "bob"));
+    assert_eq!(vec![(project_id, U128(20))], emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()));
+}
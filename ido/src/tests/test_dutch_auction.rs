@@ -0,0 +1,136 @@
+use crate::*;
+use crate::tests::test_emulator::*;
+
+/// The price drops 10 steps during the sales period.
+const DECAY_PERIOD: Timestamp = (SALE_END_DATE - SALE_START_DATE) / 10;
+
+fn new_dutch_auction_project(start_rate_numberator: u64) -> ProjectInput {
+    new_project_input("dutch.near", "", SaleType::DutchAuction {
+        start_rate_numberator,
+        decay_period: DECAY_PERIOD,
+        clearing_rate: None,
+    })
+}
+
+fn init_dutch_auction() -> (Emulator, ProjectId) {
+    init_sales_project(new_dutch_auction_project(20), &["bob", "carol"])
+}
+
+fn end_dutch_auction(emulator: &mut Emulator, project_id: ProjectId) {
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+}
+
+#[test]
+fn test_dutch_auction_rate_decays() {
+    let (emulator, project_id) = init_dutch_auction();
+    let project = emulator.contract.internal_get_project_or_panic(project_id);
+
+    assert_eq!(20f64, project.get_dutch_auction_rate(20, DECAY_PERIOD, SALE_START_DATE).get_rate());
+    // The price doesn't change until a full decay period has passed
+    assert_eq!(20f64, project.get_dutch_auction_rate(20, DECAY_PERIOD, SALE_START_DATE + DECAY_PERIOD - 1).get_rate());
+    assert_eq!(19f64, project.get_dutch_auction_rate(20, DECAY_PERIOD, SALE_START_DATE + DECAY_PERIOD).get_rate());
+    assert_eq!(15f64, project.get_dutch_auction_rate(20, DECAY_PERIOD, SALE_START_DATE + 5 * DECAY_PERIOD).get_rate());
+    // The price never drops below the floor price
+    assert_eq!(10f64, project.get_dutch_auction_rate(20, DECAY_PERIOD, SALE_END_DATE + DECAY_PERIOD).get_rate());
+}
+
+#[test]
+#[should_panic(expected = "The start price of a dutch auction must not be less than token_sale_rate.")]
+fn test_create_dutch_auction_with_start_price_below_floor() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+
+    emulator.create_project(new_dutch_auction_project(5));
+}
+
+#[test]
+fn test_commit_dutch_auction_is_capped_at_current_price() {
+    let (mut emulator, project_id) = init_dutch_auction();
+    // Price: 20, the auction is sold out at 100 * 20 = 2000
+    emulator.set_block_timestamp(SALE_START_DATE);
+
+    assert_eq!(1500, emulator.contract.internal_commit(project_id, &"bob".to_string(), 1500));
+    assert_eq!(500, emulator.contract.internal_commit(project_id, &"carol".to_string(), 1000));
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(2000, project.total_fund_committed.0);
+}
+
+#[test]
+#[should_panic(expected = "The auction is sold out at the current price.")]
+fn test_commit_dutch_auction_when_sold_out() {
+    let (mut emulator, project_id) = init_dutch_auction();
+    emulator.set_block_timestamp(SALE_START_DATE);
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 2000);
+
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 1);
+}
+
+#[test]
+fn test_dutch_auction_settles_at_clearing_price() {
+    let (mut emulator, project_id) = init_dutch_auction();
+    // Price: 15, the auction is sold out at 100 * 15 = 1500
+    emulator.set_block_timestamp(SALE_START_DATE + 5 * DECAY_PERIOD);
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 905);
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 595);
+
+    end_dutch_auction(&mut emulator, project_id);
+
+    let project = emulator.contract.internal_get_project_or_panic(project_id);
+    assert_eq!(ProjectStatus::Distribution, project.status);
+    if let SaleType::DutchAuction { clearing_rate: Some(clearing_rate), .. } = project.sale_type {
+        assert_eq!(15f64, clearing_rate.get_rate());
+    } else {
+        panic!("The clearing rate is not set.");
+    }
+
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(60, bob_distribution.unlocked_amount.0);
+    assert_eq!(5, bob_distribution.refund_amount.0);
+
+    let carol_distribution = emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap();
+    assert_eq!(39, carol_distribution.unlocked_amount.0);
+    assert_eq!(10, carol_distribution.refund_amount.0);
+}
+
+#[test]
+fn test_dutch_auction_settles_at_floor_price_when_undersubscribed() {
+    let (mut emulator, project_id) = init_dutch_auction();
+    emulator.set_block_timestamp(SALE_START_DATE);
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 600);
+
+    end_dutch_auction(&mut emulator, project_id);
+
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(60, bob_distribution.unlocked_amount.0);
+    assert_eq!(0, bob_distribution.refund_amount.0);
+}
+
+#[test]
+fn test_claim_refund() {
+    let (mut emulator, project_id) = init_dutch_auction();
+    emulator.set_block_timestamp(SALE_START_DATE + 5 * DECAY_PERIOD);
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 905);
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 595);
+    end_dutch_auction(&mut emulator, project_id);
+
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim_refund(project_id);
+
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(5, bob_distribution.refunded_amount.0);
+}
+
+#[test]
+#[should_panic(expected = "The account has nothing to refund.")]
+fn test_claim_refund_twice() {
+    let (mut emulator, project_id) = init_dutch_auction();
+    emulator.set_block_timestamp(SALE_START_DATE + 5 * DECAY_PERIOD);
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 905);
+    end_dutch_auction(&mut emulator, project_id);
+
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.claim_refund(project_id);
+    emulator.contract.claim_refund(project_id);
+}
@@ -1,9 +1,75 @@
 use near_sdk::{AccountId, MockedBlockchain, PromiseResult, VMContext, testing_env};
 use near_sdk::{Balance, BlockHeight, EpochHeight};
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_contract_standards::storage_management::StorageManagement;
+use std::convert::TryFrom;
 
 use crate::*;
 use crate::tests::test_utils::*;
 
+pub const WHITELIST_START_DATE: Timestamp = 1651172401000000000;
+pub const SALE_START_DATE: Timestamp = 1651604401000000000;
+pub const SALE_END_DATE: Timestamp = 1652068717000000000;
+
+/// A project that sells 100 tokens at the rate of 10, the whitelist period ends when the sale starts.
+/// The sale token is "contract.{owner_id}" and the fund is NEAR if the fund contract is empty.
+pub fn new_project_input(owner_id: &str, fund_contract_id: &str, sale_type: SaleType) -> ProjectInput {
+    ProjectInput {
+        owner_id: owner_id.to_string(),
+        whitelist_start_date: WHITELIST_START_DATE,
+        whitelist_end_date: SALE_START_DATE,
+        sale_start_date: SALE_START_DATE,
+        sale_end_date: SALE_END_DATE,
+        token_contract_id: format!("contract.{}", owner_id),
+        token_raised_amount: U128(100),
+        token_sale_rate_numberator: 10u64,
+        token_sale_rate_denominator: 1u64,
+        fund_contract_id: fund_contract_id.to_string(),
+        whitelist_type: WhitelistType::None,
+        sale_type,
+        distribution_type: DistributionType::Unlocked,
+    }
+}
+
+/// Create the project, whitelist the accounts then move the project to the sales period.
+pub fn init_sales_project(project_input: ProjectInput, account_ids: &[&str]) -> (Emulator, ProjectId) {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let project_id = emulator.create_project(project_input);
+    emulator.contract.internal_change_project_status(project_id);
+
+    for account_id in account_ids {
+        emulator.set_account_id_and_desposit(account_id.to_string(), account_id.to_string(), 0);
+        emulator.register_storage(account_id.to_string());
+        emulator.contract.register_whitelist(project_id, None);
+    }
+
+    emulator.set_account_id_and_desposit("alice".to_string(), "owner".to_string(), 0);
+    emulator.set_block_timestamp(SALE_START_DATE);
+    emulator.contract.internal_change_project_status(project_id);
+
+    (emulator, project_id)
+}
+
+/// Commit the deposits of the accounts then distribute the project after the sale ends.
+pub fn distribute_project(emulator: &mut Emulator, project_id: ProjectId, commits: &[(&str, Balance)]) {
+    for (account_id, deposit) in commits {
+        emulator.contract.internal_commit(project_id, &account_id.to_string(), *deposit);
+    }
+
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+}
+
+/// A FCFS project of 100 tokens in which bob buys 60 tokens, then the project is distributed.
+pub fn init_distributed_project() -> (Emulator, ProjectId) {
+    let project_input = new_project_input("token.near", "ft_contract", SaleType::Fcfs { max_allocation_per_user: 1000 });
+    let (mut emulator, project_id) = init_sales_project(project_input, &["bob"]);
+    distribute_project(&mut emulator, project_id, &[("bob", 600)]);
+
+    (emulator, project_id)
+}
+
 pub struct Emulator {
     pub contract: IDOContract,
     pub context: VMContext,
@@ -50,6 +116,14 @@ impl Emulator {
         testing_env!(self.context.clone());
     }
 
+    /// Move to another block, keep the remaining properties the same.
+    pub fn set_block_index_and_random_seed(&mut self, block_index: BlockHeight, random_seed: Vec<u8>) {
+        self.context.block_index = block_index;
+        self.context.random_seed = random_seed;
+
+        testing_env!(self.context.clone());
+    }
+
     /// Keep the remaining properties the same.
     pub fn set_account_id_and_desposit(&mut self, predecessor_account_id: String, signer_account_id:String, deposit: Balance) {
         self.context.predecessor_account_id = predecessor_account_id.clone();
@@ -61,6 +135,42 @@ impl Emulator {
         testing_env!(self.context.clone());
     }
 
+    /// Deposit storage for an account, keep the remaining properties the same.
+    pub fn register_storage(&mut self, account_id: AccountId) {
+        let context = self.context.clone();
+
+        self.context.predecessor_account_id = account_id.clone();
+        self.context.attached_deposit = ONE_NEAR;
+        testing_env!(self.context.clone());
+
+        self.contract.storage_deposit(Some(ValidAccountId::try_from(account_id).unwrap()), None);
+
+        self.context = context;
+        testing_env!(self.context.clone());
+    }
+
+    /// Create a project and load the decimals of its tokens as if ft_metadata was resolved.
+    /// Both tokens get the decimals of the fund, so the amounts are converted at the plain rate.
+    pub fn create_project(&mut self, project_input: ProjectInput) -> ProjectId {
+        let project_id = self.contract.create_project(project_input);
+        self.load_project_decimals(project_id);
+
+        project_id
+    }
+
+    pub fn create_sample_projects(&mut self) {
+        self.contract.create_sample_projects();
+        for project_id in 1..=self.contract.projects.len() {
+            self.load_project_decimals(project_id);
+        }
+    }
+
+    fn load_project_decimals(&mut self, project_id: ProjectId) {
+        let decimals = self.contract.internal_get_project_or_panic(project_id).fund_decimals.unwrap_or(NEAR_DECIMALS);
+        self.contract.internal_set_project_decimals(project_id, false, decimals);
+        self.contract.internal_set_project_decimals(project_id, true, decimals);
+    }
+
     pub fn update_account_sale_ticket(&mut self, account_id: AccountId, project_id: ProjectId, ticket_nums: u64){
         self.contract.assert_test_mode_and_owner();
         let mut project_account_unordered_map = self.contract.internal_get_accounts_by_project_or_panic(project_id);
@@ -71,7 +181,8 @@ impl Emulator {
                     eligible_tickets: ticket_nums,
                     deposit_tickets: 0,
                     ticket_ids: vec![],
-                    win_ticket_ids: vec![]
+                    win_ticket_ids: vec![],
+                    tier: Tier::default(),
                 }
             )
         };
@@ -81,7 +192,7 @@ impl Emulator {
             distribution_data: None
         };
 
-        project_account_unordered_map.insert(&account_id, &project_account);
+        project_account_unordered_map.insert(&account_id, &UpgradableProjectAccount::from(project_account));
         self.contract.accounts_by_project.insert(&project_id,&project_account_unordered_map);   
     }
 }
@@ -0,0 +1,71 @@
+use near_sdk::json_types::U128;
+use near_sdk::PromiseOrValue;
+
+use crate::*;
+use crate::tests::test_emulator::*;
+
+fn init_fcfs_project() -> (Emulator, ProjectId) {
+    init_sales_project(new_project_input("fcfs.near", "ft_contract", SaleType::Fcfs { max_allocation_per_user: 600 }), &["bob", "carol"])
+}
+
+#[test]
+fn test_commit_fcfs_is_capped() {
+    let (mut emulator, project_id) = init_fcfs_project();
+
+    // Capped by max_allocation_per_user
+    assert_eq!(600, emulator.contract.internal_commit(project_id, &"bob".to_string(), 700));
+    // Capped by the hard cap: 100 * 10 = 1000
+    assert_eq!(400, emulator.contract.internal_commit(project_id, &"carol".to_string(), 500));
+
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(60, bob_distribution.unlocked_amount.0);
+
+    let carol_distribution = emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap();
+    assert_eq!(40, carol_distribution.unlocked_amount.0);
+}
+
+#[test]
+#[should_panic(expected = "The project is sold out.")]
+fn test_commit_fcfs_when_sold_out() {
+    let (mut emulator, project_id) = init_fcfs_project();
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 600);
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 400);
+
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 1);
+}
+
+#[test]
+#[should_panic(expected = "The account has reached max_allocation.")]
+fn test_commit_fcfs_when_max_allocation_reached() {
+    let (mut emulator, project_id) = init_fcfs_project();
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 600);
+
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 1);
+}
+
+#[test]
+fn test_ft_on_transfer_fcfs_returns_remaining() {
+    let (mut emulator, project_id) = init_fcfs_project();
+
+    emulator.update_context("ft_contract".to_string(), "bob".to_string(), 0);
+    let result = emulator.contract.ft_on_transfer("bob".to_string(), U128(700), format!("project_id:{}", project_id));
+
+    if let PromiseOrValue::Value(unused_amount) = result {
+        assert_eq!(100, unused_amount.0);
+    } else {
+        panic!("The unused amount is not returned.");
+    }
+}
+
+#[test]
+fn test_fcfs_distribution_keeps_bought_tokens() {
+    let (mut emulator, project_id) = init_fcfs_project();
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 250);
+
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(25, bob_distribution.unlocked_amount.0);
+    assert_eq!(0, bob_distribution.refund_amount.0);
+}
@@ -37,7 +37,7 @@ fn test_happy_case() {
     env::log(format!("Before create a project").as_bytes());
 
     // Create a new project with status of Preparation
-    let project_id = emulator.contract.create_project(project);
+    let project_id = emulator.create_project(project);
 
     let created_project = emulator.contract.get_project(project_id);
     if let Some(created_project) = created_project {
@@ -56,7 +56,7 @@ fn test_happy_case() {
     assert_eq!(before_whitelist_time, emulator.context.block_timestamp);
 
     emulator.set_account_id_and_desposit(owner(), owner(), 0);
-    emulator.contract.update_project_whitelist_date(project_id, None, None);
+    emulator.contract.update_project_whitelist_date(project_id);
 
     emulator.contract.change_project_status(project_id);
 
@@ -65,7 +65,8 @@ fn test_happy_case() {
 
     // User A registers whitelist
     emulator.set_account_id_and_desposit(account_a(), account_a(), 0);
-    emulator.contract.register_whitelist(project_id);
+    emulator.register_storage(account_a());
+    emulator.contract.register_whitelist(project_id, None);
     println!("User A registers whitelist - {}", account_a());
     assert!(emulator.contract.is_whitelist(project_id, account_a().to_string()));
 
@@ -101,7 +102,7 @@ fn test_happy_case() {
     emulator.contract.process_update_staking_tickets(project_id, account_a(), account_json);
 
     // Validate stored data
-    let project = emulator.contract.projects.get(&project_id).unwrap();
+    let project = emulator.contract.projects.get(&project_id).map(Project::from).unwrap();
     println!("Staking tier -> Total tickets");
     
     
@@ -139,13 +140,11 @@ fn test_happy_case() {
     emulator.set_account_id_and_desposit(owner(), owner(), 0);
     emulator.contract.update_project_sales_date(project_id);
 
-    println!("update_project_sales_date");
-
     emulator.contract.close_project_whitelist(project_id);
 
     println!("close_project_whitelist");
 
-    let project = emulator.contract.projects.get(&project_id).unwrap();
+    let project = emulator.contract.projects.get(&project_id).map(Project::from).unwrap();
     assert_eq!(ProjectStatus::Sales, project.status);
 
     // User A deposit fund
@@ -158,7 +157,7 @@ fn test_happy_case() {
 fn test_project_ticket() {
     let mut emulator = Emulator::default();
     let project = get_project_1();
-    let project_id = emulator.contract.create_project(project);
+    let project_id = emulator.create_project(project);
 
     println!("Start testing...");
 
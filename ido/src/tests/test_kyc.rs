@@ -0,0 +1,108 @@
+use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey};
+use near_sdk::json_types::{Base58PublicKey, Base64VecU8, U64};
+
+use crate::*;
+use crate::modules::kyc::{get_kyc_message, KycAttestation};
+use crate::tests::test_emulator::*;
+
+const EXPIRY: Timestamp = 1651604401000000000;
+
+fn get_provider_secret_key(seed: u8) -> SecretKey {
+    SecretKey::from_bytes(&[seed; 32]).unwrap()
+}
+
+fn get_provider_public_key(seed: u8) -> Base58PublicKey {
+    let public_key: PublicKey = (&get_provider_secret_key(seed)).into();
+    Base58PublicKey([vec![0u8], public_key.to_bytes().to_vec()].concat())
+}
+
+fn sign_kyc_attestation(seed: u8, account_id: &str, project_id: ProjectId, expiry: Timestamp) -> KycAttestation {
+    let secret_key = get_provider_secret_key(seed);
+    let public_key: PublicKey = (&secret_key).into();
+    let message = get_kyc_message(&account_id.to_string(), project_id, expiry);
+    let signature = ExpandedSecretKey::from(&secret_key).sign(message.as_bytes(), &public_key);
+
+    KycAttestation {
+        expiry: U64(expiry),
+        signature: Base64VecU8(signature.to_bytes().to_vec()),
+    }
+}
+
+/// Project 1 requires KYC, the provider key is created from the seed 1.
+fn init_kyc_project() -> Emulator {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(1);
+    emulator.contract.set_project_kyc_required(1, true);
+    emulator.contract.add_kyc_provider_key(get_provider_public_key(1));
+
+    emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+
+    emulator
+}
+
+#[test]
+fn test_register_whitelist_with_kyc_attestation() {
+    let mut emulator = init_kyc_project();
+    assert!(emulator.contract.get_project(1).unwrap().kyc_required);
+
+    emulator.contract.register_whitelist(1, Some(sign_kyc_attestation(1, "bob", 1, EXPIRY)));
+
+    assert!(emulator.contract.is_whitelist(1, "bob".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "The project requires a KYC attestation.")]
+fn test_register_whitelist_without_kyc_attestation() {
+    let mut emulator = init_kyc_project();
+
+    emulator.contract.register_whitelist(1, None);
+}
+
+#[test]
+#[should_panic(expected = "The KYC attestation is expired.")]
+fn test_register_whitelist_with_expired_kyc_attestation() {
+    let mut emulator = init_kyc_project();
+
+    emulator.contract.register_whitelist(1, Some(sign_kyc_attestation(1, "bob", 1, 1651172401000000000)));
+}
+
+#[test]
+#[should_panic(expected = "The KYC attestation is not signed by a KYC provider.")]
+fn test_register_whitelist_with_unknown_provider() {
+    let mut emulator = init_kyc_project();
+
+    emulator.contract.register_whitelist(1, Some(sign_kyc_attestation(2, "bob", 1, EXPIRY)));
+}
+
+#[test]
+#[should_panic(expected = "The KYC attestation is not signed by a KYC provider.")]
+fn test_register_whitelist_with_attestation_of_other_account() {
+    let mut emulator = init_kyc_project();
+
+    emulator.contract.register_whitelist(1, Some(sign_kyc_attestation(1, "carol", 1, EXPIRY)));
+}
+
+#[test]
+fn test_manage_kyc_provider_keys() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+
+    emulator.contract.add_kyc_provider_key(get_provider_public_key(1));
+    emulator.contract.add_kyc_provider_key(get_provider_public_key(2));
+    assert_eq!(vec![get_provider_public_key(1), get_provider_public_key(2)], emulator.contract.get_kyc_provider_keys());
+
+    emulator.contract.remove_kyc_provider_key(get_provider_public_key(1));
+    assert_eq!(vec![get_provider_public_key(2)], emulator.contract.get_kyc_provider_keys());
+}
+
+#[test]
+#[should_panic(expected = "You are not an admin of this contract.")]
+fn test_add_kyc_provider_key_by_non_admin() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("bob".to_string(), "bob".to_string(), 0);
+
+    emulator.contract.add_kyc_provider_key(get_provider_public_key(1));
+}
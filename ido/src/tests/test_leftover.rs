@@ -0,0 +1,120 @@
+use near_sdk::json_types::U128;
+
+use crate::*;
+use crate::modules::leftover::*;
+use crate::tests::test_emulator::*;
+
+const LEFTOVER_END_DATE: Timestamp = 1652100000000000000;
+
+fn new_fcfs_project() -> ProjectInput {
+    new_project_input("token.near", "ft_contract", SaleType::Fcfs { max_allocation_per_user: 1000 })
+}
+
+fn new_leftover_sale(eligibility: LeftoverEligibility) -> LeftoverSale {
+    LeftoverSale {
+        eligibility,
+        max_allocation_per_user: 300,
+        end_date: LEFTOVER_END_DATE,
+        total_fund_committed: 0,
+    }
+}
+
+/// Bob buys 60 of the 100 tokens and carol only registers the whitelist, then the leftover sale starts.
+fn init_leftover_sale() -> (Emulator, ProjectId) {
+    let (mut emulator, project_id) = init_sales_project(new_fcfs_project(), &["bob", "carol"]);
+    emulator.contract.set_project_leftover_sale(project_id, Some(new_leftover_sale(LeftoverEligibility::Whitelist)));
+    distribute_project(&mut emulator, project_id, &[("bob", 600)]);
+
+    (emulator, project_id)
+}
+
+#[test]
+fn test_leftover_sale_starts_after_distribution() {
+    let (emulator, project_id) = init_leftover_sale();
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(ProjectStatus::LeftoverSales, project.status);
+    assert_eq!(U128(40), project.unsold_token_amount);
+}
+
+#[test]
+fn test_commit_leftover() {
+    let (mut emulator, project_id) = init_leftover_sale();
+
+    // Carol is capped by the max allocation, bob buys the rest
+    assert_eq!(300, emulator.contract.internal_commit(project_id, &"carol".to_string(), 500));
+    assert_eq!(100, emulator.contract.internal_commit(project_id, &"bob".to_string(), 200));
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(U128(0), project.unsold_token_amount);
+    assert_eq!(400, project.leftover_sale.unwrap().total_fund_committed);
+    assert_eq!(U128(1000), project.total_fund_committed);
+    // Bob was counted in the main sale
+    assert_eq!(2, emulator.contract.get_project_stats(project_id).committed_accounts);
+
+    // The claims start after the leftover sale ends
+    emulator.set_block_timestamp(LEFTOVER_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+    assert_eq!(ProjectStatus::Distribution, emulator.contract.get_project(project_id).unwrap().status);
+    assert_eq!(vec![(project_id, U128(30))], emulator.contract.internal_claim_rounds(project_id, &"carol".to_string()));
+    assert_eq!(vec![(project_id, U128(70))], emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()));
+}
+
+#[test]
+fn test_commit_leftover_twice_counts_the_account_once() {
+    let (mut emulator, project_id) = init_leftover_sale();
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 100);
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 100);
+
+    assert_eq!(2, emulator.contract.get_project_stats(project_id).committed_accounts);
+}
+
+#[test]
+fn test_withdraw_fund_includes_leftover_sale() {
+    let (mut emulator, project_id) = init_leftover_sale();
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 300);
+    emulator.set_block_timestamp(LEFTOVER_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+    emulator.set_account_id_and_desposit("token.near".to_string(), "token.near".to_string(), 1);
+
+    emulator.contract.withdraw_fund(project_id);
+
+    assert_eq!(U128(900), emulator.contract.get_project(project_id).unwrap().withdrawn_fund_amount);
+}
+
+#[test]
+#[should_panic(expected = "The leftover tokens are sold out.")]
+fn test_commit_leftover_when_sold_out() {
+    let (mut emulator, project_id) = init_leftover_sale();
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 300);
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 300);
+
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 100);
+}
+
+#[test]
+#[should_panic(expected = "The leftover sale is over.")]
+fn test_commit_leftover_after_end_date() {
+    let (mut emulator, project_id) = init_leftover_sale();
+    emulator.set_block_timestamp(LEFTOVER_END_DATE + 1);
+
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 100);
+}
+
+#[test]
+#[should_panic(expected = "The leftover sale is not end.")]
+fn test_end_leftover_sale_before_end_date() {
+    let (mut emulator, project_id) = init_leftover_sale();
+
+    emulator.contract.internal_change_project_status(project_id);
+}
+
+#[test]
+#[should_panic(expected = "The project isn't a lottery.")]
+fn test_set_leftover_sale_for_lottery_losers_of_non_lottery() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let project_id = emulator.create_project(new_fcfs_project());
+
+    emulator.contract.set_project_leftover_sale(project_id, Some(new_leftover_sale(LeftoverEligibility::LotteryLosers)));
+}
@@ -0,0 +1,316 @@
+use near_sdk::json_types::U128;
+
+use crate::*;
+use crate::modules::lottery::*;
+use crate::modules::leftover::*;
+use crate::tests::test_emulator::*;
+use crate::tests::test_staking_tier::*;
+
+const SECRET: &str = "lottery secret";
+
+/// The hard cap is 10 * 10 = 100 so that only one ticket of 100 wins.
+fn new_lottery_project() -> ProjectInput {
+    ProjectInput {
+        token_raised_amount: U128(10),
+        whitelist_type: WhitelistType::Ticket,
+        ..new_project_input("lottery.near", "ft_contract", SaleType::Lottery {
+            allocation_per_ticket: 100,
+            total_tickets: 0,
+            win_ticket_ids: None,
+        })
+    }
+}
+
+fn get_ticket(ticket_id: TicketId, weight: u32, guaranteed: bool) -> LotteryTicket {
+    LotteryTicket { ticket_id, weight, guaranteed }
+}
+
+/// A lottery project in the sales period without accounts.
+fn init_lottery_sales() -> (Emulator, ProjectId) {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let project_id = emulator.create_project(new_lottery_project());
+    emulator.contract.update_project_status(project_id, ProjectStatus::Sales);
+    emulator.set_block_timestamp(SALE_START_DATE);
+
+    (emulator, project_id)
+}
+
+/// Reveal the secret after the sale ends and record the seeds of the following blocks.
+fn complete_lottery_draw(emulator: &mut Emulator, project_id: ProjectId) {
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.set_block_index_and_random_seed(100, vec![1; 32]);
+    emulator.contract.reveal_lottery_secret(project_id, SECRET.to_string());
+
+    for block_index in 101..(100 + LOTTERY_BLOCK_SEEDS as u64) {
+        emulator.set_block_index_and_random_seed(block_index, vec![block_index as u8; 32]);
+        emulator.contract.record_lottery_block_seed(project_id);
+    }
+}
+
+/// Whitelist the accounts with their staking tiers, commit their deposits then distribute the project.
+fn distribute_lottery(accounts: &[(&str, Tier, Balance)]) -> (Emulator, ProjectId) {
+    distribute_lottery_with_leftover_sale(accounts, None)
+}
+
+fn distribute_lottery_with_leftover_sale(accounts: &[(&str, Tier, Balance)], leftover_sale: Option<LeftoverSale>) -> (Emulator, ProjectId) {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.config.tier_configs.insert(Tier::Tier4, TierConfig::new(0, 100, 1).with_lottery(100, true));
+    let project_id = emulator.create_project(new_lottery_project());
+    emulator.contract.internal_change_project_status(project_id);
+
+    for (account_id, tier, _) in accounts {
+        emulator.update_context("alice".to_string(), account_id.to_string(), 0);
+        emulator.register_storage(account_id.to_string());
+        emulator.contract.register_whitelist(project_id, None);
+
+        let mut account_json = get_sample_account_json(&account_id.to_string());
+        account_json.tier = *tier;
+        emulator.contract.process_update_staking_tickets(project_id, account_id.to_string(), account_json);
+    }
+
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.update_project_status(project_id, ProjectStatus::Sales);
+    emulator.set_block_timestamp(SALE_START_DATE);
+    for (account_id, _, deposit) in accounts {
+        if *deposit > 0 {
+            emulator.contract.internal_commit(project_id, &account_id.to_string(), *deposit);
+        }
+    }
+    emulator.contract.commit_lottery_secret(project_id, get_lottery_secret_hash(SECRET));
+    emulator.contract.set_project_leftover_sale(project_id, leftover_sale);
+
+    complete_lottery_draw(&mut emulator, project_id);
+    emulator.contract.internal_change_project_status(project_id);
+
+    (emulator, project_id)
+}
+
+#[test]
+fn test_draw_win_tickets_is_reproducible() {
+    let get_tickets = || (0..20).map(|ticket_id| get_ticket(ticket_id, 100, false)).collect::<Vec<LotteryTicket>>();
+
+    let win_ticket_ids = draw_win_tickets(&[1u8; 32], get_tickets(), 5);
+
+    assert_eq!(5, win_ticket_ids.len());
+    assert_eq!(win_ticket_ids, draw_win_tickets(&[1u8; 32], get_tickets(), 5));
+}
+
+#[test]
+fn test_draw_win_tickets_by_weight() {
+    let tickets = vec![get_ticket(0, 0, false), get_ticket(1, 150, false), get_ticket(2, 0, false), get_ticket(3, 100, false)];
+
+    // The tickets without weight never win
+    assert_eq!(vec![1, 3], draw_win_tickets(&[2u8; 32], tickets, 3));
+}
+
+#[test]
+fn test_draw_win_tickets_with_guaranteed_tickets() {
+    let tickets = vec![get_ticket(0, 100, false), get_ticket(1, 100, false), get_ticket(2, 0, true), get_ticket(3, 0, true)];
+
+    // The guaranteed tickets win first in the ticket order
+    assert_eq!(vec![2], draw_win_tickets(&[3u8; 32], tickets, 1));
+}
+
+#[test]
+fn test_process_update_staking_tickets_sets_tier() {
+    let (emulator, project_id) = distribute_lottery(&[("bob", Tier::Tier2, 300)]);
+
+    let lottery_sale_data = emulator.contract.get_project_account_info(project_id, "bob".to_string()).sale_data.unwrap().lottery_sale_data.unwrap();
+    assert_eq!(Tier::Tier2, lottery_sale_data.tier);
+    assert_eq!(12, lottery_sale_data.eligible_tickets);
+    assert_eq!(3, lottery_sale_data.deposit_tickets);
+}
+
+#[test]
+fn test_lottery_guarantees_a_win_ticket_for_tier4() {
+    let (emulator, project_id) = distribute_lottery(&[("carol", Tier::Tier2, 1000), ("bob", Tier::Tier4, 100)]);
+
+    let project = emulator.contract.internal_get_project_or_panic(project_id);
+    let win_ticket_ids = match &project.sale_type {
+        SaleType::Lottery { win_ticket_ids: Some(win_ticket_ids), .. } => win_ticket_ids.clone(),
+        _ => panic!("The win tickets are not drawn."),
+    };
+    assert_eq!(vec![10], win_ticket_ids);
+
+    let bob_sale = emulator.contract.get_project_account_info(project_id, "bob".to_string()).sale_data.unwrap();
+    assert_eq!(vec![10], bob_sale.lottery_sale_data.unwrap().win_ticket_ids);
+    let carol_sale = emulator.contract.get_project_account_info(project_id, "carol".to_string()).sale_data.unwrap();
+    assert!(carol_sale.lottery_sale_data.unwrap().win_ticket_ids.is_empty());
+
+    // The draw is reproducible from the stored seed
+    let seed = project.lottery_seed.unwrap();
+    assert_eq!(win_ticket_ids, draw_win_tickets(&seed, emulator.contract.internal_get_lottery_tickets(project_id), 1));
+    assert_eq!(1, emulator.contract.get_project_stats(project_id).total_win_tickets);
+}
+
+#[test]
+fn test_lottery_refunds_lost_tickets() {
+    let (mut emulator, project_id) = distribute_lottery(&[("carol", Tier::Tier2, 1000), ("bob", Tier::Tier4, 200)]);
+
+    // Bob wins his guaranteed ticket and loses the other one, carol loses all 10 tickets
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(10, bob_distribution.unlocked_amount.0);
+    assert_eq!(100, bob_distribution.refund_amount.0);
+    let carol_distribution = emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap();
+    assert_eq!(0, carol_distribution.unlocked_amount.0);
+    assert_eq!(1000, carol_distribution.refund_amount.0);
+    assert_eq!(1100, emulator.contract.get_project_stats(project_id).total_refund_amount.0);
+
+    // Carol has no token to claim but her fund is refundable
+    assert!(emulator.contract.internal_claim_rounds(project_id, &"carol".to_string()).is_empty());
+    emulator.set_account_id_and_desposit("carol".to_string(), "carol".to_string(), 1);
+    emulator.contract.claim_refund(project_id);
+    let carol_distribution = emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap();
+    assert_eq!(1000, carol_distribution.refunded_amount.0);
+}
+
+#[test]
+fn test_lottery_commit_is_not_claimable_before_the_draw() {
+    let (mut emulator, project_id) = init_lottery_sales();
+    emulator.register_storage("bob".to_string());
+    emulator.contract.internal_add_account(&"bob".to_string(), project_id, None);
+    emulator.update_account_sale_ticket("bob".to_string(), project_id, 1);
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 100);
+
+    assert_eq!(0, emulator.contract.internal_claim(project_id, &"bob".to_string()));
+}
+
+#[test]
+fn test_overflow_commit_is_not_claimable_before_the_distribution() {
+    let (mut emulator, project_id) = init_sales_project(new_project_input("overflow.near", "", SaleType::Overflow), &["bob"]);
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 100);
+
+    assert_eq!(0, emulator.contract.internal_claim(project_id, &"bob".to_string()));
+}
+
+#[test]
+fn test_lottery_seed_is_derived_from_the_secret_and_block_seeds() {
+    let (emulator, project_id) = distribute_lottery(&[("bob", Tier::Tier2, 300)]);
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    let lottery_draw = project.lottery_draw.unwrap();
+    assert_eq!(Some(SECRET.to_string()), lottery_draw.secret);
+    assert_eq!(vec![100, 101, 102], lottery_draw.block_seeds.iter().map(|(block_height, _)| *block_height).collect::<Vec<BlockHeight>>());
+
+    let inputs = format!("{}:{}:{}:{}", SECRET, to_hex(&[1; 32]), to_hex(&[101; 32]), to_hex(&[102; 32]));
+    assert_eq!(Some(to_hex(&env::sha256(inputs.as_bytes()))), project.lottery_seed);
+}
+
+#[test]
+#[should_panic(expected = "The lottery draw isn't completed.")]
+fn test_distribute_lottery_before_block_seeds_are_recorded() {
+    let (mut emulator, project_id) = init_lottery_sales();
+    emulator.contract.commit_lottery_secret(project_id, get_lottery_secret_hash(SECRET));
+
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.reveal_lottery_secret(project_id, SECRET.to_string());
+    emulator.contract.internal_change_project_status(project_id);
+}
+
+#[test]
+#[should_panic(expected = "The lottery secret doesn't match the committed hash.")]
+fn test_reveal_wrong_lottery_secret() {
+    let (mut emulator, project_id) = init_lottery_sales();
+    emulator.contract.commit_lottery_secret(project_id, get_lottery_secret_hash(SECRET));
+
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.reveal_lottery_secret(project_id, "another secret".to_string());
+}
+
+#[test]
+#[should_panic(expected = "The lottery secret must be committed before the sale ends.")]
+fn test_commit_lottery_secret_after_sales() {
+    let (mut emulator, project_id) = init_lottery_sales();
+
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.commit_lottery_secret(project_id, get_lottery_secret_hash(SECRET));
+}
+
+#[test]
+#[should_panic(expected = "The seed of this block is already recorded.")]
+fn test_record_lottery_block_seed_twice_in_a_block() {
+    let (mut emulator, project_id) = init_lottery_sales();
+    emulator.contract.commit_lottery_secret(project_id, get_lottery_secret_hash(SECRET));
+
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.reveal_lottery_secret(project_id, SECRET.to_string());
+    emulator.contract.record_lottery_block_seed(project_id);
+}
+
+/// Record the seeds of the blocks after the reveal deadline without the secret.
+fn record_lottery_block_seeds_after_deadline(emulator: &mut Emulator, project_id: ProjectId) {
+    emulator.set_block_timestamp(SALE_END_DATE + LOTTERY_REVEAL_PERIOD + 1);
+    for block_index in 100..(100 + LOTTERY_BLOCK_SEEDS as u64) {
+        emulator.set_block_index_and_random_seed(block_index, vec![block_index as u8; 32]);
+        emulator.contract.record_lottery_block_seed(project_id);
+    }
+}
+
+#[test]
+fn test_lottery_is_drawn_from_block_seeds_when_the_secret_isnt_revealed() {
+    let (mut emulator, project_id) = init_lottery_sales();
+    emulator.contract.commit_lottery_secret(project_id, get_lottery_secret_hash(SECRET));
+
+    record_lottery_block_seeds_after_deadline(&mut emulator, project_id);
+    emulator.contract.internal_change_project_status(project_id);
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(ProjectStatus::Distribution, project.status);
+    assert_eq!(None, project.lottery_draw.unwrap().secret);
+    let inputs = format!("{}:{}:{}", to_hex(&[100; 32]), to_hex(&[101; 32]), to_hex(&[102; 32]));
+    assert_eq!(Some(to_hex(&env::sha256(inputs.as_bytes()))), project.lottery_seed);
+}
+
+#[test]
+fn test_lottery_is_drawn_from_block_seeds_when_the_secret_isnt_committed() {
+    let (mut emulator, project_id) = init_lottery_sales();
+
+    record_lottery_block_seeds_after_deadline(&mut emulator, project_id);
+    emulator.contract.internal_change_project_status(project_id);
+
+    assert_eq!(ProjectStatus::Distribution, emulator.contract.get_project(project_id).unwrap().status);
+}
+
+#[test]
+#[should_panic(expected = "The lottery secret isn't revealed.")]
+fn test_record_lottery_block_seed_before_the_reveal_deadline() {
+    let (mut emulator, project_id) = init_lottery_sales();
+    emulator.contract.commit_lottery_secret(project_id, get_lottery_secret_hash(SECRET));
+
+    emulator.set_block_timestamp(SALE_END_DATE + LOTTERY_REVEAL_PERIOD);
+    emulator.contract.record_lottery_block_seed(project_id);
+}
+
+#[test]
+#[should_panic(expected = "The reveal deadline of the lottery secret has passed.")]
+fn test_reveal_lottery_secret_after_the_deadline() {
+    let (mut emulator, project_id) = init_lottery_sales();
+    emulator.contract.commit_lottery_secret(project_id, get_lottery_secret_hash(SECRET));
+
+    emulator.set_block_timestamp(SALE_END_DATE + LOTTERY_REVEAL_PERIOD + 1);
+    emulator.contract.reveal_lottery_secret(project_id, SECRET.to_string());
+}
+
+#[test]
+fn test_lottery_losers_buy_in_leftover_sale() {
+    // Carol doesn't buy any ticket, so the only win ticket of the hard cap is unsold
+    let leftover_sale = LeftoverSale {
+        eligibility: LeftoverEligibility::LotteryLosers,
+        max_allocation_per_user: 100,
+        end_date: SALE_END_DATE + 1000,
+        total_fund_committed: 0,
+    };
+    let (mut emulator, project_id) = distribute_lottery_with_leftover_sale(&[("carol", Tier::Tier2, 0)], Some(leftover_sale));
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(ProjectStatus::LeftoverSales, project.status);
+    assert_eq!(U128(10), project.unsold_token_amount);
+
+    assert_eq!(100, emulator.contract.internal_commit(project_id, &"carol".to_string(), 150));
+
+    let carol_distribution = emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap();
+    assert_eq!(10, carol_distribution.unlocked_amount.0);
+    assert_eq!(100, carol_distribution.leftover_committed_amount.0);
+    assert_eq!(U128(0), emulator.contract.get_project(project_id).unwrap().unsold_token_amount);
+}
@@ -0,0 +1,249 @@
+use near_sdk::{env, testing_env, MockedBlockchain};
+
+use crate::tests::test_utils::*;
+use crate::migration::*;
+use crate::*;
+
+fn get_old_project_1() -> ProjectV1 {
+    ProjectV1 {
+        owner_id: "your.testnet".to_string(),
+        whitelist_start_date: 1,
+        whitelist_end_date: 2,
+        sale_start_date: 3,
+        sale_end_date: 5,
+        token_contract_id: "your.testnet".to_string(),
+        token_raised_amount: 40000,
+        token_sale_rate: Rate::new(10, 1),
+        fund_contract_id: "usdc.testnet".to_string(),
+        total_fund_committed: 75,
+        status: ProjectStatus::Sales,
+        whitelist_type: WhitelistType::None,
+        sale_type: SaleType::Shared {
+            min_allocation_per_user: 50,
+            max_allocation_per_user: 100,
+        },
+        distribution_type: DistributionType::Unlocked,
+    }
+}
+
+fn write_old_state() {
+    let mut old_contract = OldIDOContract {
+        owner_id: owner(),
+        staking_contract_id: staking(),
+        funding_ft_token_ids: UnorderedSet::new(get_storage_key(StorageKey::FundingTokenIdKey)),
+        projects: UnorderedMap::new(get_storage_key(StorageKey::ProjectKey)),
+        accounts_by_project: LookupMap::new(get_storage_key(StorageKey::AccountsByProjectKey)),
+        tickets_by_project: LookupMap::new(get_storage_key(StorageKey::TicketsByProjectKey)),
+        projects_by_account: LookupMap::new(get_storage_key(StorageKey::ProjectsByAccountKey)),
+        test_mode_enabled: true,
+        config: ConfigV1 {
+            tier_configs: TierConfig::get_default_tier_configs().into_iter()
+                .map(|(tier, tier_config)| (tier, TierConfigV1 {
+                    min_point: tier_config.min_point,
+                    ticket: tier_config.ticket,
+                    allocation: tier_config.allocation,
+                }))
+                .collect(),
+        },
+    };
+
+    old_contract.projects.insert(&1, &get_old_project_1());
+
+    let mut old_accounts: UnorderedMap<AccountId, ProjectAccountV1> = UnorderedMap::new(get_storage_key(StorageKey::AccountsByProjectInnerKey(1)));
+    old_accounts.insert(&bob(), &ProjectAccountV1 {
+        sale_data: Some(AccountSaleV1 {
+            committed_amount: 75,
+            sale_data: AccountSaleDataV1::Shared,
+        }),
+        distribution_data: None,
+    });
+    old_contract.accounts_by_project.insert(&1, &old_accounts);
+    old_contract.tickets_by_project.insert(&1, &LookupMap::new(get_storage_key(StorageKey::TicketsByProjectInnerKey(1))));
+
+    let mut projects_in_account: ProjectIdUnorderedSet = UnorderedSet::new(get_storage_key(StorageKey::ProjectsByAccountInnerKey {
+        account_id_hash: hash_account_id(&bob())
+    }));
+    projects_in_account.insert(&1);
+    old_contract.projects_by_account.insert(&bob(), &projects_in_account);
+
+    env::state_write(&old_contract);
+}
+
+#[test]
+fn test_migrate_from_old_layout() {
+    let context = VMContextBuilder::new()
+        .current_account_id(owner())
+        .predecessor_account_id(owner())
+        .signer_account_id(owner())
+        .finish();
+    testing_env!(context);
+
+    write_old_state();
+
+    let mut contract = IDOContract::migrate();
+    assert!(contract.is_paused());
+    assert_eq!(U64(0), contract.migrate_accounts(1, 10));
+    assert!(contract.get_account_migrations().is_empty());
+
+    assert_eq!(owner(), contract.owner_id);
+    assert_eq!(staking(), contract.staking_contract_id);
+    assert_eq!(1, contract.get_projects(None, None, None).len());
+
+    let project = contract.get_project(1).unwrap();
+    assert_eq!(ProjectStatus::Sales, project.status);
+    assert_eq!(U128(40000), project.token_raised_amount);
+    assert_eq!(U128(75), project.total_fund_committed);
+    assert_eq!(U128(400000), project.hard_cap);
+    assert_eq!(1, project.whitelist_accounts);
+
+    assert!(contract.is_whitelist(1, bob()));
+    let account = contract.get_project_account_info(1, bob());
+    assert!(account.is_whitelist);
+    assert_eq!(U128(75), account.sale_data.unwrap().committed_amount);
+    assert!(account.distribution_data.is_none());
+}
+
+#[test]
+fn test_migrated_state_is_readable() {
+    let context = VMContextBuilder::new()
+        .current_account_id(owner())
+        .predecessor_account_id(owner())
+        .signer_account_id(owner())
+        .finish();
+    testing_env!(context);
+
+    write_old_state();
+
+    let mut contract = IDOContract::migrate();
+    contract.migrate_accounts(1, 10);
+    env::state_write(&contract);
+
+    let contract: IDOContract = env::state_read().unwrap();
+    let project = contract.internal_get_project_or_panic(1);
+    assert_eq!(75, project.total_fund_committed);
+
+    let account = contract.internal_get_account_by_project_or_panic(1, &bob());
+    assert_eq!(75, account.sale_data.unwrap().committed_amount);
+}
+
+#[test]
+fn test_migrated_project_is_upgraded_when_saved() {
+    let context = VMContextBuilder::new()
+        .current_account_id(owner())
+        .predecessor_account_id(owner())
+        .signer_account_id(owner())
+        .finish();
+    testing_env!(context);
+
+    write_old_state();
+
+    let mut contract = IDOContract::migrate();
+    contract.migrate_accounts(1, 10);
+    assert!(matches!(contract.projects.get(&1), Some(UpgradableProject::V1(_))));
+    assert!(matches!(contract.internal_get_accounts_by_project_or_panic(1).get(&bob()), Some(UpgradableProjectAccount::V1(_))));
+
+    let project = contract.internal_get_project_or_panic(1);
+    contract.internal_save_project(1, project);
+    let project_account = contract.internal_get_account_by_project_or_panic(1, &bob());
+    contract.internal_save_account_by_project(1, &bob(), project_account);
+
+    assert!(matches!(contract.projects.get(&1), Some(UpgradableProject::Current(_))));
+    assert!(matches!(contract.internal_get_accounts_by_project_or_panic(1).get(&bob()), Some(UpgradableProjectAccount::Current(_))));
+}
+
+#[test]
+fn test_migrated_lottery_account_gets_tier_from_tickets() {
+    let context = VMContextBuilder::new()
+        .current_account_id(owner())
+        .predecessor_account_id(owner())
+        .signer_account_id(owner())
+        .finish();
+    testing_env!(context);
+
+    write_old_state();
+    // A lottery account with the tickets of Tier2
+    let mut old_accounts_by_project: LookupMap<ProjectId, UnorderedMap<AccountId, ProjectAccountV1>> = LookupMap::new(get_storage_key(StorageKey::AccountsByProjectKey));
+    let mut old_accounts = old_accounts_by_project.get(&1).unwrap();
+    old_accounts.insert(&alice(), &ProjectAccountV1 {
+        sale_data: Some(AccountSaleV1 {
+            committed_amount: 0,
+            sale_data: AccountSaleDataV1::Lottery(LotteryAccountSaleDataV1 {
+                eligible_tickets: 12,
+                deposit_tickets: 0,
+                ticket_ids: vec![],
+                win_ticket_ids: vec![],
+            }),
+        }),
+        distribution_data: None,
+    });
+    old_accounts_by_project.insert(&1, &old_accounts);
+
+    let mut contract = IDOContract::migrate();
+    contract.migrate_accounts(1, 10);
+
+    assert!(matches!(contract.internal_get_accounts_by_project_or_panic(1).get(&alice()), Some(UpgradableProjectAccount::Current(_))));
+    match contract.internal_get_account_by_project_or_panic(1, &alice()).sale_data.unwrap().sale_data {
+        AccountSaleData::Lottery(lottery_sale_data) => assert_eq!(Tier::Tier2, lottery_sale_data.tier),
+        _ => panic!("The account isn't a lottery account."),
+    }
+}
+
+#[test]
+fn test_migrate_accounts_in_pages() {
+    let context = VMContextBuilder::new()
+        .current_account_id(owner())
+        .predecessor_account_id(owner())
+        .signer_account_id(owner())
+        .finish();
+    testing_env!(context);
+
+    write_old_state();
+    let mut old_accounts_by_project: LookupMap<ProjectId, UnorderedMap<AccountId, ProjectAccountV1>> = LookupMap::new(get_storage_key(StorageKey::AccountsByProjectKey));
+    let mut old_accounts = old_accounts_by_project.get(&1).unwrap();
+    old_accounts.insert(&alice(), &ProjectAccountV1 {
+        sale_data: Some(AccountSaleV1 {
+            committed_amount: 50,
+            sale_data: AccountSaleDataV1::Shared,
+        }),
+        distribution_data: None,
+    });
+    old_accounts_by_project.insert(&1, &old_accounts);
+
+    let mut contract = IDOContract::migrate();
+    assert_eq!(vec![1], contract.get_account_migrations());
+
+    assert_eq!(U64(1), contract.migrate_accounts(1, 1));
+    assert_eq!(vec![1], contract.get_account_migrations());
+    assert_eq!(U64(0), contract.migrate_accounts(1, 1));
+    assert!(contract.get_account_migrations().is_empty());
+
+    assert_eq!(75, contract.internal_get_account_by_project_or_panic(1, &bob()).sale_data.unwrap().committed_amount);
+    assert_eq!(50, contract.internal_get_account_by_project_or_panic(1, &alice()).sale_data.unwrap().committed_amount);
+    contract.set_paused(false);
+}
+
+#[test]
+#[should_panic(expected = "The project accounts aren't migrated yet.")]
+fn test_unpause_before_accounts_are_migrated() {
+    let context = VMContextBuilder::new()
+        .current_account_id(owner())
+        .predecessor_account_id(owner())
+        .signer_account_id(owner())
+        .finish();
+    testing_env!(context);
+
+    write_old_state();
+    let mut contract = IDOContract::migrate();
+
+    contract.set_paused(false);
+}
+
+#[test]
+fn test_tier_from_eligible_tickets() {
+    let tier_configs = TierConfig::get_default_tier_configs();
+
+    assert_eq!(Tier::Tier0, get_tier_from_eligible_tickets(&tier_configs, 0));
+    assert_eq!(Tier::Tier1, get_tier_from_eligible_tickets(&tier_configs, 5));
+    // Tier3 and Tier4 give the same tickets
+    assert_eq!(Tier::Tier3, get_tier_from_eligible_tickets(&tier_configs, 100));
+}
@@ -0,0 +1,73 @@
+use crate::*;
+use crate::tests::test_emulator::*;
+
+fn init_overflow_project() -> (Emulator, ProjectId) {
+    init_sales_project(new_project_input("overflow.near", "", SaleType::Overflow), &["bob", "carol"])
+}
+
+#[test]
+fn test_commit_overflow_exceeds_hard_cap() {
+    let (mut emulator, project_id) = init_overflow_project();
+
+    // The hard cap is 100 * 10 = 1000 but the whole deposit is accepted
+    assert_eq!(1500, emulator.contract.internal_commit(project_id, &"bob".to_string(), 1500));
+    assert_eq!(500, emulator.contract.internal_commit(project_id, &"carol".to_string(), 500));
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(2000, project.total_fund_committed.0);
+    assert_eq!(1000, project.hard_cap.0);
+}
+
+#[test]
+fn test_overflow_distributes_pro_rata_when_oversubscribed() {
+    let (mut emulator, project_id) = init_overflow_project();
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 1500);
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 500);
+
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(75, bob_distribution.unlocked_amount.0);
+    assert_eq!(750, bob_distribution.refund_amount.0);
+
+    let carol_distribution = emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap();
+    assert_eq!(25, carol_distribution.unlocked_amount.0);
+    assert_eq!(250, carol_distribution.refund_amount.0);
+}
+
+#[test]
+fn test_overflow_distributes_at_fixed_price_when_undersubscribed() {
+    let (mut emulator, project_id) = init_overflow_project();
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 300);
+
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(30, bob_distribution.unlocked_amount.0);
+    assert_eq!(0, bob_distribution.refund_amount.0);
+}
+
+#[test]
+fn test_overflow_distributes_pro_rata_with_token_decimals() {
+    // 100 tokens of 18 decimals for NEAR of 24 decimals, the product of the raw amounts doesn't fit in u128
+    let one_token: Balance = 1_000_000_000_000_000_000;
+    let project_input = ProjectInput {
+        token_raised_amount: U128(100 * one_token),
+        ..new_project_input("overflow.near", "", SaleType::Overflow)
+    };
+    let (mut emulator, project_id) = init_sales_project(project_input, &["bob", "carol"]);
+    emulator.contract.internal_set_project_decimals(project_id, false, 18);
+    assert_eq!(1000 * ONE_NEAR, emulator.contract.get_project(project_id).unwrap().hard_cap.0);
+
+    distribute_project(&mut emulator, project_id, &[("bob", 1500 * ONE_NEAR), ("carol", 500 * ONE_NEAR)]);
+
+    let bob_distribution = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(75 * one_token, bob_distribution.unlocked_amount.0);
+    assert_eq!(750 * ONE_NEAR, bob_distribution.refund_amount.0);
+
+    let carol_distribution = emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap();
+    assert_eq!(25 * one_token, carol_distribution.unlocked_amount.0);
+    assert_eq!(250 * ONE_NEAR, carol_distribution.refund_amount.0);
+}
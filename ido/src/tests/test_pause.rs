@@ -0,0 +1,92 @@
+use near_sdk::json_types::U128;
+use near_sdk::PromiseOrValue;
+
+use crate::tests::test_emulator::*;
+use crate::modules::project::ProjectStatus;
+
+fn init_sale_project() -> Emulator {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+
+    emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.internal_change_project_status(1);
+    emulator.contract.register_whitelist(1, None);
+
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.update_project_status(1, ProjectStatus::Sales);
+    emulator.set_block_timestamp(1651804401000000000);
+
+    emulator
+}
+
+#[test]
+#[should_panic(expected = "You are not an admin of this contract.")]
+fn test_set_paused_by_non_admin() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("bob".to_string(), "bob".to_string(), 0);
+
+    emulator.contract.set_paused(true);
+}
+
+#[test]
+fn test_set_paused_by_admin() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("owner".to_string(), "owner".to_string(), 0);
+    emulator.contract.add_admin("alice".to_string());
+    assert_eq!(vec!["alice".to_string()], emulator.contract.get_admin_ids());
+
+    emulator.update_context("alice".to_string(), "alice".to_string(), 0);
+    emulator.contract.set_paused(true);
+    assert!(emulator.contract.is_paused());
+
+    emulator.contract.set_paused(false);
+    assert!(!emulator.contract.is_paused());
+}
+
+#[test]
+#[should_panic(expected = "The contract is paused.")]
+fn test_register_whitelist_when_contract_paused() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(1);
+    emulator.contract.set_paused(true);
+
+    emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.register_whitelist(1, None);
+}
+
+#[test]
+#[should_panic(expected = "The project is paused.")]
+fn test_commit_when_project_paused() {
+    let mut emulator = init_sale_project();
+    emulator.contract.set_project_paused(1, true);
+    assert!(emulator.contract.get_project(1).unwrap().paused);
+
+    emulator.contract.internal_commit(1, &"bob".to_string(), 50);
+}
+
+#[test]
+fn test_ft_on_transfer_when_project_paused() {
+    let mut emulator = init_sale_project();
+    emulator.contract.set_project_paused(1, true);
+
+    emulator.update_context("ft_contract".to_string(), "bob".to_string(), 0);
+    let result = emulator.contract.ft_on_transfer("bob".to_string(), U128(50), "project_id:1".to_string());
+
+    if let PromiseOrValue::Value(unused_amount) = result {
+        assert_eq!(U128(50), unused_amount, "The deposited tokens must be refunded.");
+    } else {
+        panic!("ft_on_transfer must return the unused amount.");
+    }
+
+    // The project is resumed
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.set_block_timestamp(1651804401000000000);
+    emulator.contract.set_project_paused(1, false);
+    emulator.contract.internal_commit(1, &"bob".to_string(), 50);
+    assert_eq!(U128(50), emulator.contract.get_project(1).unwrap().total_fund_committed);
+}
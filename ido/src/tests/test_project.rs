@@ -29,7 +29,7 @@ fn test_create_and_get_project() {
     let mut emulator = Emulator::default();
 
     let project = get_project_1();
-    emulator.contract.create_project(get_project_1());
+    emulator.create_project(get_project_1());
     let projects = emulator.contract.get_projects(None, None, None); 
 
     assert_eq!(1, projects.len(), "The number of projects in the contract is not correct!");
@@ -50,7 +50,7 @@ fn test_create_and_get_project() {
 fn test_create_sample_projects() {
     let mut emulator = Emulator::default();
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
-    emulator.contract.create_sample_projects();
+    emulator.create_sample_projects();
 
     // let projects = emulator.contract.get_projects(None, None, None); 
 
@@ -61,7 +61,7 @@ fn test_create_sample_projects() {
 fn test_update_project_sales_date_to_end() {
     let mut emulator = Emulator::default();
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
-    emulator.contract.create_sample_projects();
+    emulator.create_sample_projects();
 
     let whitelist_start_date = 1640995200000000000;
     let whitelist_end_date = 1641250800000000000;
@@ -75,7 +75,7 @@ fn test_update_project_sales_date_to_end() {
     project.sale_start_date = sale_start_date;
     project.sale_end_date = sale_end_date;
 
-    let project_id = emulator.contract.create_project(project);
+    let project_id = emulator.create_project(project);
     let project = emulator.contract.internal_get_project_or_panic(project_id);
 
     // Preparation
@@ -85,7 +85,7 @@ fn test_update_project_sales_date_to_end() {
     let before_whitelist_time = decrease_timestamp(&whitelist_start_date, 1, 0, 0, 0);
     emulator.set_block_timestamp(before_whitelist_time);
     emulator.set_account_id_and_desposit(owner(), owner(), 0);
-    emulator.contract.update_project_whitelist_date(project_id, None, None);
+    emulator.contract.update_project_whitelist_date(project_id);
 
     println!("Project's status: {:?}, Current Time: {}, Whitelist Start Time: {}", project.status, before_whitelist_time, whitelist_start_date);
     emulator.contract.change_project_status(project_id);
@@ -106,4 +106,143 @@ fn test_update_project_sales_date_to_end() {
 
     let project = emulator.contract.internal_get_project_or_panic(project_id);
     assert_eq!(ProjectStatus::Distribution, project.status);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_token_sale_rate_decimal_string() {
+    assert_eq!("10", Rate::new(10, 1).to_decimal_string());
+    assert_eq!("0.01", Rate::new(1, 100).to_decimal_string());
+    assert_eq!("1.25", Rate::new(5, 4).to_decimal_string());
+    assert_eq!(format!("0.{}", "3".repeat(MAX_RATE_FRACTION_DIGITS)), Rate::new(1, 3).to_decimal_string());
+}
+
+#[cfg(test)]
+fn resolve_ft_metadata(emulator: &mut Emulator, project_id: ProjectId, is_fund_token: bool, decimals: u8) {
+    let mut context = emulator.context.clone();
+    context.predecessor_account_id = context.current_account_id.clone();
+    let metadata = format!("{{\"spec\":\"ft-1.0.0\",\"name\":\"Token\",\"symbol\":\"TKN\",\"icon\":null,\"reference\":null,\"reference_hash\":null,\"decimals\":{}}}", decimals);
+    testing_env_with_promise_results(context, PromiseResult::Successful(metadata.into_bytes()));
+
+    emulator.contract.resolve_ft_metadata(project_id, is_fund_token);
+}
+
+#[test]
+fn test_hard_cap_with_token_decimals() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let project_id = emulator.contract.create_project(get_project_1());
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!("10", project.token_sale_rate);
+    assert_eq!(None, project.token_decimals);
+
+    // 8 decimals of the sale token and 6 decimals of the fund token
+    resolve_ft_metadata(&mut emulator, project_id, false, 8);
+    resolve_ft_metadata(&mut emulator, project_id, true, 6);
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(Some(8), project.token_decimals);
+    assert_eq!(Some(6), project.fund_decimals);
+    // 0.0004 TOKEN * 10 = 0.004 USDC
+    assert_eq!(4000, project.hard_cap.0);
+}
+
+#[test]
+fn test_near_fund_decimals() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let mut project = get_project_1();
+    project.fund_contract_id = "".to_string();
+    let project_id = emulator.contract.create_project(project);
+
+    assert_eq!(Some(NEAR_DECIMALS), emulator.contract.get_project(project_id).unwrap().fund_decimals);
+}
+
+#[test]
+#[should_panic(expected = "The token metadata of the project isn't loaded.")]
+fn test_change_project_status_before_metadata_is_loaded() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let project_id = emulator.contract.create_project(new_project_input("token.near", "ft_contract", SaleType::Fcfs { max_allocation_per_user: 1000 }));
+
+    emulator.contract.change_project_status(project_id);
+}
+
+#[test]
+#[should_panic(expected = "The token metadata of the project isn't loaded.")]
+fn test_commit_before_metadata_is_loaded() {
+    let (mut emulator, project_id) = init_sales_project(new_project_input("token.near", "ft_contract", SaleType::Fcfs { max_allocation_per_user: 1000 }), &["bob"]);
+    // The fund token metadata failed to load
+    let mut project = emulator.contract.internal_get_project_or_panic(project_id);
+    project.fund_decimals = None;
+    emulator.contract.internal_save_project(project_id, project);
+
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 100);
+}
+
+#[test]
+fn test_set_project_decimals() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let project_id = emulator.contract.create_project(get_project_1());
+
+    emulator.contract.set_project_decimals(project_id, Some(8), Some(6));
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(Some(8), project.token_decimals);
+    assert_eq!(Some(6), project.fund_decimals);
+}
+
+#[test]
+#[should_panic(expected = "The loaded decimals can't be changed after the project starts.")]
+fn test_set_loaded_project_decimals_after_the_project_starts() {
+    let (mut emulator, project_id) = init_sales_project(new_project_input("token.near", "ft_contract", SaleType::Fcfs { max_allocation_per_user: 1000 }), &[]);
+
+    emulator.contract.set_project_decimals(project_id, Some(8), None);
+}
+
+#[test]
+#[should_panic(expected = "You are not an admin of this contract.")]
+fn test_load_project_metadata_by_non_admin() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let project_id = emulator.contract.create_project(get_project_1());
+
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 0);
+    emulator.contract.load_project_metadata(project_id);
+}
+
+#[test]
+fn test_resolve_ft_metadata_keeps_the_decimals_set_by_admin() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let project_id = emulator.contract.create_project(get_project_1());
+    emulator.contract.set_project_decimals(project_id, Some(8), None);
+
+    resolve_ft_metadata(&mut emulator, project_id, false, 18);
+    resolve_ft_metadata(&mut emulator, project_id, true, 6);
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(Some(8), project.token_decimals);
+    assert_eq!(Some(6), project.fund_decimals);
+}
+
+#[test]
+fn test_shared_distributes_pro_rata_when_oversubscribed() {
+    let project_input = new_project_input("token.near", "", SaleType::Shared { min_allocation_per_user: 0, max_allocation_per_user: 2000 });
+    let (mut emulator, project_id) = init_sales_project(project_input, &["bob", "carol"]);
+    distribute_project(&mut emulator, project_id, &[("bob", 1500), ("carol", 500)]);
+
+    assert_eq!(75, emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap().unlocked_amount.0);
+    assert_eq!(25, emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap().unlocked_amount.0);
+}
+
+#[test]
+fn test_shared_distributes_at_sale_rate_when_undersubscribed() {
+    let project_input = new_project_input("token.near", "", SaleType::Shared { min_allocation_per_user: 0, max_allocation_per_user: 2000 });
+    let (mut emulator, project_id) = init_sales_project(project_input, &["bob", "carol"]);
+    distribute_project(&mut emulator, project_id, &[("bob", 300), ("carol", 100)]);
+
+    assert_eq!(30, emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap().unlocked_amount.0);
+    assert_eq!(10, emulator.contract.get_project_account_info(project_id, "carol".to_string()).distribution_data.unwrap().unlocked_amount.0);
+}
@@ -0,0 +1,102 @@
+use near_sdk::json_types::U128;
+
+use crate::*;
+use crate::modules::round::ProjectRoundInput;
+use crate::tests::test_emulator::*;
+
+const ROUND_WHITELIST_START_DATE: Timestamp = 1652068717000000000;
+const ROUND_SALE_START_DATE: Timestamp = 1652100000000000000;
+const ROUND_SALE_END_DATE: Timestamp = 1652200000000000000;
+
+fn new_round(sale_start_date: Timestamp) -> ProjectRoundInput {
+    ProjectRoundInput {
+        whitelist_start_date: ROUND_WHITELIST_START_DATE,
+        whitelist_end_date: sale_start_date,
+        sale_start_date,
+        sale_end_date: ROUND_SALE_END_DATE,
+        token_raised_amount: U128(50),
+        token_sale_rate_numberator: 20u64,
+        token_sale_rate_denominator: 1u64,
+        whitelist_type: WhitelistType::None,
+        sale_type: SaleType::Fcfs {
+            max_allocation_per_user: 1000,
+        },
+        distribution_type: DistributionType::Unlocked,
+    }
+}
+
+fn init_project_with_round() -> (Emulator, ProjectId, ProjectId) {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    let project_id = emulator.create_project(new_project_input("round.near", "ft_contract", SaleType::Fcfs { max_allocation_per_user: 600 }));
+    let round_id = emulator.contract.add_project_round(project_id, new_round(ROUND_SALE_START_DATE));
+
+    (emulator, project_id, round_id)
+}
+
+#[test]
+fn test_add_project_round() {
+    let (emulator, project_id, round_id) = init_project_with_round();
+
+    let rounds = emulator.contract.get_project_rounds(project_id);
+    assert_eq!(2, rounds.len());
+    assert_eq!(vec![round_id], rounds[0].round_ids);
+    assert_eq!(round_id, rounds[1].id);
+    assert_eq!(Some(project_id), rounds[1].parent_id);
+    assert_eq!("contract.round.near".to_string(), rounds[1].token_contract_id);
+    assert_eq!("20", rounds[1].token_sale_rate);
+
+    // The rounds can be listed from any round
+    assert_eq!(2, emulator.contract.get_project_rounds(round_id).len());
+}
+
+#[test]
+#[should_panic(expected = "The round must start after the sales period of the previous round.")]
+fn test_add_project_round_before_previous_round() {
+    let (mut emulator, project_id, _) = init_project_with_round();
+
+    emulator.contract.add_project_round(project_id, new_round(SALE_END_DATE));
+}
+
+#[test]
+#[should_panic(expected = "A round can't own rounds.")]
+fn test_add_project_round_to_round() {
+    let (mut emulator, _, round_id) = init_project_with_round();
+
+    emulator.contract.add_project_round(round_id, new_round(ROUND_SALE_END_DATE));
+}
+
+#[test]
+fn test_claim_aggregates_rounds() {
+    let (mut emulator, project_id, round_id) = init_project_with_round();
+    let bob = "bob".to_string();
+
+    // Buy 60 tokens in the main project
+    emulator.contract.internal_change_project_status(project_id);
+    emulator.set_account_id_and_desposit(bob.clone(), bob.clone(), 0);
+    emulator.register_storage(bob.clone());
+    emulator.contract.register_whitelist(project_id, None);
+    emulator.set_account_id_and_desposit("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.update_project_status(project_id, ProjectStatus::Sales);
+    emulator.set_block_timestamp(SALE_START_DATE);
+    emulator.contract.internal_commit(project_id, &bob, 600);
+
+    // Buy 20 tokens in the round
+    emulator.set_block_timestamp(ROUND_WHITELIST_START_DATE + 1);
+    emulator.contract.internal_change_project_status(round_id);
+    emulator.set_account_id_and_desposit(bob.clone(), bob.clone(), 0);
+    emulator.contract.register_whitelist(round_id, None);
+    emulator.set_account_id_and_desposit("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.update_project_status(round_id, ProjectStatus::Sales);
+    emulator.set_block_timestamp(ROUND_SALE_START_DATE);
+    emulator.contract.internal_commit(round_id, &bob, 400);
+
+    // Only the distributed rounds are claimed
+    emulator.contract.update_project_status(project_id, ProjectStatus::Distribution);
+    emulator.set_block_timestamp(ROUND_SALE_END_DATE + 1);
+    assert_eq!(vec![(project_id, U128(60))], emulator.contract.internal_claim_rounds(project_id, &bob));
+
+    emulator.contract.update_project_status(round_id, ProjectStatus::Distribution);
+    assert_eq!(vec![(project_id, U128(60)), (round_id, U128(20))], emulator.contract.internal_claim_rounds(project_id, &bob));
+    assert_eq!(vec![(project_id, U128(60)), (round_id, U128(20))], emulator.contract.internal_claim_rounds(round_id, &bob));
+}
@@ -0,0 +1,58 @@
+use crate::tests::test_emulator::*;
+use crate::tests::test_staking_tier::*;
+
+use crate::*;
+
+const XTOKEN_PROJECT_ID: ProjectId = 3;
+
+/// Project 3 is an XToken project which requires 100 points.
+fn init_xtoken_project(min_stake_days: u32) -> Emulator {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(XTOKEN_PROJECT_ID);
+    emulator.contract.set_project_min_stake_days(XTOKEN_PROJECT_ID, min_stake_days);
+
+    emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+
+    emulator
+}
+
+fn register_with_locked_timestamp(emulator: &mut Emulator, locked_timestamp: Timestamp) -> bool {
+    let account_json = get_account_json(&"bob".to_string(), 500_00000000, 30, locked_timestamp);
+    emulator.contract.proccess_register_whitelist("bob".to_string(), XTOKEN_PROJECT_ID, account_json, 100)
+}
+
+#[test]
+fn test_register_whitelist_with_old_enough_stake() {
+    let mut emulator = init_xtoken_project(7);
+    assert_eq!(7, emulator.contract.get_project(XTOKEN_PROJECT_ID).unwrap().min_stake_days);
+
+    assert!(register_with_locked_timestamp(&mut emulator, WHITELIST_START_DATE - 7 * ONE_DAY_IN_NANOSECONDS));
+    assert!(emulator.contract.is_whitelist(XTOKEN_PROJECT_ID, "bob".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "The stake hasn't been locked for min_stake_days before the whitelist start date.")]
+fn test_register_whitelist_with_recent_stake() {
+    let mut emulator = init_xtoken_project(7);
+
+    register_with_locked_timestamp(&mut emulator, WHITELIST_START_DATE - 6 * ONE_DAY_IN_NANOSECONDS);
+}
+
+#[test]
+fn test_register_whitelist_without_stake_age_requirement() {
+    let mut emulator = init_xtoken_project(0);
+
+    assert!(register_with_locked_timestamp(&mut emulator, WHITELIST_START_DATE + ONE_DAY_IN_NANOSECONDS));
+    assert!(emulator.contract.is_whitelist(XTOKEN_PROJECT_ID, "bob".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "You are not an admin of this contract.")]
+fn test_set_project_min_stake_days_by_non_admin() {
+    let mut emulator = init_xtoken_project(0);
+
+    emulator.contract.set_project_min_stake_days(XTOKEN_PROJECT_ID, 7);
+}
@@ -13,6 +13,7 @@ pub(crate) fn get_account_json(account_id: &AccountId, locked_balance: u128, loc
         account_id: account_id.clone(),
         locked_balance: U128::from(locked_balance),
         locked_days: locked_days,
+        locked_timestamp: locked_timestamp,
         unlocked_timestamp: locked_timestamp,
         staked_balance: U128::from(locked_balance),
         unstaked_balance: U128::from(0),
@@ -0,0 +1,84 @@
+use crate::*;
+use crate::tests::test_emulator::*;
+use crate::tests::test_staking_tier::*;
+
+fn init_overflow_project() -> (Emulator, ProjectId) {
+    init_sales_project(new_project_input("overflow.near", "", SaleType::Overflow), &["bob", "carol", "dave"])
+}
+
+#[test]
+fn test_project_stats_of_whitelist_and_commits() {
+    let (mut emulator, project_id) = init_overflow_project();
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 300);
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 200);
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 250);
+
+    let project_stats = emulator.contract.get_project_stats(project_id);
+    assert_eq!(3, project_stats.whitelist_accounts);
+    assert!(project_stats.whitelist_accounts_by_tier.is_empty());
+    assert_eq!(2, project_stats.committed_accounts);
+    assert_eq!(750, project_stats.total_fund_committed.0);
+    assert_eq!(1000, project_stats.hard_cap.0);
+    assert_eq!(75f64, project_stats.fill_percentage);
+}
+
+#[test]
+fn test_project_stats_of_distribution_claims_and_refunds() {
+    let (mut emulator, project_id) = init_overflow_project();
+    emulator.contract.internal_commit(project_id, &"bob".to_string(), 1500);
+    emulator.contract.internal_commit(project_id, &"carol".to_string(), 500);
+
+    emulator.set_block_timestamp(SALE_END_DATE + 1);
+    emulator.contract.internal_change_project_status(project_id);
+
+    let project_stats = emulator.contract.get_project_stats(project_id);
+    assert_eq!(200f64, project_stats.fill_percentage);
+    assert_eq!(100, project_stats.total_distributed_amount.0);
+    assert_eq!(1000, project_stats.total_refund_amount.0);
+
+    emulator.contract.internal_update_claimed_amount(project_id, &"bob".to_string(), 75);
+    emulator.contract.internal_refund(project_id, &"bob".to_string());
+
+    let project_stats = emulator.contract.get_project_stats(project_id);
+    assert_eq!(75, project_stats.total_claimed_amount.0);
+    assert_eq!(750, project_stats.total_refunded_amount.0);
+}
+
+#[test]
+fn test_project_stats_of_lottery_tickets() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(2);
+
+    emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.register_whitelist(2, None);
+
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.update_project_status(2, ProjectStatus::Sales);
+    emulator.update_account_sale_ticket("bob".to_string(), 2, 6);
+    emulator.set_block_timestamp(1651804401000000000);
+    emulator.contract.internal_commit(2, &"bob".to_string(), 25);
+    emulator.contract.internal_commit(2, &"bob".to_string(), 30);
+
+    let project_stats = emulator.contract.get_project_stats(2);
+    assert_eq!(5, project_stats.total_tickets);
+    assert_eq!(1, project_stats.committed_accounts);
+}
+
+#[test]
+fn test_project_stats_of_staking_tiers() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(3);
+
+    emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.proccess_register_whitelist("bob".to_string(), 3, get_sample_account_json(&"bob".to_string()), 100);
+
+    let project_stats = emulator.contract.get_project_stats(3);
+    assert_eq!(1, project_stats.whitelist_accounts);
+    assert_eq!(Some(&1), project_stats.whitelist_accounts_by_tier.get(&Tier::Tier1));
+}
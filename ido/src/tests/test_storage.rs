@@ -0,0 +1,156 @@
+use near_contract_standards::storage_management::StorageManagement;
+use near_sdk::json_types::{ValidAccountId, U128};
+use std::convert::TryFrom;
+
+use crate::tests::test_emulator::*;
+use crate::modules::storage::*;
+use crate::*;
+
+fn valid_account_id(account_id: &str) -> ValidAccountId {
+    ValidAccountId::try_from(account_id.to_string()).unwrap()
+}
+
+fn get_min_storage_balance(emulator: &Emulator) -> Balance {
+    emulator.contract.storage_balance_bounds().min.0
+}
+
+#[test]
+fn test_storage_deposit() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("bob".to_string(), "bob".to_string(), ONE_NEAR);
+
+    let storage_balance = emulator.contract.storage_deposit(None, None);
+    let min_balance = get_min_storage_balance(&emulator);
+
+    assert_eq!(U128(ONE_NEAR), storage_balance.total);
+    assert_eq!(U128(ONE_NEAR - min_balance), storage_balance.available);
+    assert_eq!(storage_balance.total, emulator.contract.storage_balance_of(valid_account_id("bob")).unwrap().total);
+    assert!(emulator.contract.storage_balance_of(valid_account_id("alice")).is_none());
+
+    // Deposit more
+    emulator.contract.storage_deposit(Some(valid_account_id("bob")), None);
+    let storage_balance = emulator.contract.storage_balance_of(valid_account_id("bob")).unwrap();
+    assert_eq!(U128(2 * ONE_NEAR), storage_balance.total);
+}
+
+#[test]
+fn test_storage_deposit_registration_only() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("bob".to_string(), "bob".to_string(), ONE_NEAR);
+
+    let storage_balance = emulator.contract.storage_deposit(None, Some(true));
+    let min_balance = get_min_storage_balance(&emulator);
+
+    assert_eq!(U128(min_balance), storage_balance.total);
+    assert_eq!(U128(0), storage_balance.available);
+}
+
+#[test]
+#[should_panic(expected = "The attached deposit is less than the minimum storage balance")]
+fn test_storage_deposit_less_than_min_balance() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("bob".to_string(), "bob".to_string(), 1);
+
+    emulator.contract.storage_deposit(None, None);
+}
+
+#[test]
+#[should_panic(expected = "The account is not registered. Please deposit storage first.")]
+fn test_register_whitelist_without_storage() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(1);
+
+    emulator.update_context("bob".to_string(), "bob".to_string(), 0);
+    emulator.contract.register_whitelist(1, None);
+}
+
+#[test]
+#[should_panic(expected = "Not enough storage deposit.")]
+fn test_register_whitelist_with_insufficient_storage() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(1);
+
+    let min_balance = get_min_storage_balance(&emulator);
+    emulator.update_context("bob".to_string(), "bob".to_string(), min_balance);
+    emulator.contract.storage_deposit(None, None);
+
+    emulator.update_context("bob".to_string(), "bob".to_string(), 0);
+    emulator.contract.register_whitelist(1, None);
+}
+
+#[test]
+fn test_register_whitelist_charges_storage() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(1);
+
+    emulator.update_context("bob".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.register_whitelist(1, None);
+
+    let storage_account = emulator.contract.internal_get_storage_account_or_panic(&"bob".to_string());
+    assert!(storage_account.used_bytes > STORAGE_REGISTRATION_BYTES);
+
+    let storage_balance = emulator.contract.storage_balance_of(valid_account_id("bob")).unwrap();
+    assert_eq!(ONE_NEAR - storage_account.get_used_balance(), storage_balance.available.0);
+}
+
+#[test]
+fn test_storage_withdraw() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("bob".to_string(), "bob".to_string(), ONE_NEAR);
+    emulator.contract.storage_deposit(None, None);
+    let min_balance = get_min_storage_balance(&emulator);
+
+    emulator.update_context("bob".to_string(), "bob".to_string(), 1);
+    let storage_balance = emulator.contract.storage_withdraw(Some(U128(ONE_NEAR / 2)));
+    assert_eq!(U128(ONE_NEAR / 2), storage_balance.total);
+
+    let storage_balance = emulator.contract.storage_withdraw(None);
+    assert_eq!(U128(min_balance), storage_balance.total);
+    assert_eq!(U128(0), storage_balance.available);
+}
+
+#[test]
+#[should_panic(expected = "The amount is greater than the available storage balance")]
+fn test_storage_withdraw_more_than_available() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("bob".to_string(), "bob".to_string(), ONE_NEAR);
+    emulator.contract.storage_deposit(None, None);
+
+    emulator.update_context("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.storage_withdraw(Some(U128(ONE_NEAR)));
+}
+
+#[test]
+fn test_storage_unregister() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("bob".to_string(), "bob".to_string(), ONE_NEAR);
+    emulator.contract.storage_deposit(None, None);
+
+    emulator.update_context("bob".to_string(), "bob".to_string(), 1);
+    assert!(emulator.contract.storage_unregister(None));
+    assert!(emulator.contract.storage_balance_of(valid_account_id("bob")).is_none());
+    assert!(!emulator.contract.storage_unregister(None));
+}
+
+#[test]
+#[should_panic(expected = "Cannot unregister the account that has joined 1 project(s).")]
+fn test_storage_unregister_after_joining_project() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(1);
+
+    emulator.update_context("bob".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.register_whitelist(1, None);
+
+    emulator.update_context("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.storage_unregister(None);
+}
@@ -0,0 +1,85 @@
+use crate::tests::test_emulator::*;
+use crate::modules::project::*;
+use crate::modules::ticket::*;
+
+fn init_lottery_project_with_tickets() -> Emulator {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+
+    emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.internal_change_project_status(2);
+    emulator.contract.register_whitelist(2, None);
+    emulator.update_context("alice".to_string(), "alice".to_string(), 0);
+    emulator.register_storage("alice".to_string());
+    emulator.contract.register_whitelist(2, None);
+
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.update_project_status(2, ProjectStatus::Sales);
+    emulator.update_account_sale_ticket("bob".to_string(), 2, 6);
+    emulator.update_account_sale_ticket("alice".to_string(), 2, 6);
+
+    emulator.set_block_timestamp(1651804401000000000);
+    emulator.contract.internal_commit(2, &"bob".to_string(), 20);
+    emulator.contract.internal_commit(2, &"alice".to_string(), 30);
+
+    emulator
+}
+
+#[test]
+fn test_get_ticket_owner() {
+    let emulator = init_lottery_project_with_tickets();
+
+    assert_eq!(Some("bob".to_string()), emulator.contract.get_ticket_owner(2, 0));
+    assert_eq!(Some("bob".to_string()), emulator.contract.get_ticket_owner(2, 1));
+    assert_eq!(Some("alice".to_string()), emulator.contract.get_ticket_owner(2, 2));
+    assert_eq!(None, emulator.contract.get_ticket_owner(2, 5));
+}
+
+#[test]
+fn test_get_account_tickets() {
+    let emulator = init_lottery_project_with_tickets();
+
+    assert_eq!(vec![0, 1], emulator.contract.get_account_tickets(2, "bob".to_string()));
+    assert_eq!(vec![2, 3, 4], emulator.contract.get_account_tickets(2, "alice".to_string()));
+    assert!(emulator.contract.get_account_tickets(2, "carol".to_string()).is_empty());
+}
+
+#[test]
+fn test_get_project_tickets() {
+    let emulator = init_lottery_project_with_tickets();
+
+    let tickets = emulator.contract.get_project_tickets(2, None, None);
+    assert_eq!(5, tickets.len());
+
+    let tickets = emulator.contract.get_project_tickets(2, Some(1), Some(2));
+    assert_eq!(vec![
+        TicketJson { ticket_id: 1, account_id: "bob".to_string() },
+        TicketJson { ticket_id: 2, account_id: "alice".to_string() },
+    ], tickets);
+
+    assert!(emulator.contract.get_project_tickets(2, Some(10), None).is_empty());
+    assert!(emulator.contract.get_project_tickets(1, None, None).is_empty());
+}
+
+#[test]
+fn test_get_win_tickets() {
+    let mut emulator = init_lottery_project_with_tickets();
+    assert!(emulator.contract.get_win_tickets(2).is_empty());
+
+    let mut project = emulator.contract.internal_get_project_or_panic(2);
+    if let SaleType::Lottery { allocation_per_ticket, total_tickets, .. } = project.sale_type {
+        project.sale_type = SaleType::Lottery {
+            allocation_per_ticket,
+            total_tickets,
+            win_ticket_ids: Some(vec![1, 4]),
+        };
+    }
+    emulator.contract.internal_save_project(2, project);
+
+    assert_eq!(vec![
+        TicketJson { ticket_id: 1, account_id: "bob".to_string() },
+        TicketJson { ticket_id: 4, account_id: "alice".to_string() },
+    ], emulator.contract.get_win_tickets(2));
+}
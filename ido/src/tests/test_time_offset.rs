@@ -0,0 +1,84 @@
+use near_sdk::json_types::U64;
+
+use crate::*;
+use crate::tests::test_emulator::*;
+use crate::tests::test_utils::*;
+
+const DEFAULT_BLOCK_TIMESTAMP: Timestamp = 1651199572000000000;
+const WHITELIST_END_DATE: Timestamp = 1651604401000000000;
+
+#[test]
+fn test_advance_time_moves_project_to_sales() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.contract.internal_change_project_status(1);
+
+    emulator.contract.advance_time(U64(WHITELIST_END_DATE - DEFAULT_BLOCK_TIMESTAMP));
+    emulator.contract.advance_time(U64(1));
+    emulator.contract.internal_change_project_status(1);
+
+    let project = emulator.contract.get_project(1).unwrap();
+    assert_eq!(ProjectStatus::Sales, project.status);
+    // The project dates are not changed
+    assert_eq!(WHITELIST_END_DATE, project.whitelist_end_date);
+    assert_eq!(WHITELIST_END_DATE + 1, emulator.contract.get_contract_time().0);
+}
+
+#[test]
+fn test_update_project_sales_date_moves_the_clock() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    let project = emulator.contract.get_project(1).unwrap();
+
+    emulator.contract.update_project_sales_date_to_end(1);
+
+    assert_eq!(project.sale_end_date + 1, emulator.contract.get_contract_time().0);
+    assert_eq!(project.sale_end_date, emulator.contract.get_project(1).unwrap().sale_end_date);
+}
+
+#[test]
+#[should_panic(expected = "The contract time can't be moved before the block time.")]
+fn test_update_project_whitelist_date_before_the_block_time() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.create_sample_projects();
+    emulator.set_block_timestamp(WHITELIST_END_DATE);
+
+    emulator.contract.update_project_whitelist_date(1);
+}
+
+#[test]
+fn test_set_time_offset() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+
+    emulator.contract.set_time_offset(U64(1000));
+    assert_eq!(1000, emulator.contract.get_time_offset().0);
+    assert_eq!(DEFAULT_BLOCK_TIMESTAMP + 1000, emulator.contract.get_contract_time().0);
+
+    emulator.contract.set_time_offset(U64(0));
+    assert_eq!(DEFAULT_BLOCK_TIMESTAMP, emulator.contract.get_contract_time().0);
+}
+
+#[test]
+fn test_time_offset_is_cleared_without_test_mode() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.set_time_offset(U64(1000));
+
+    let contract = IDOContract::new(owner(), ft_token_id(), None, Some(false), None);
+
+    assert_eq!(0, contract.get_time_offset().0);
+    assert_eq!(DEFAULT_BLOCK_TIMESTAMP, contract.get_contract_time().0);
+}
+
+#[test]
+#[should_panic(expected = "You are not the owner of this contract.")]
+fn test_advance_time_by_non_owner() {
+    let mut emulator = Emulator::default();
+    emulator.update_context("alice".to_string(), "bob".to_string(), 0);
+
+    emulator.contract.advance_time(U64(1000));
+}
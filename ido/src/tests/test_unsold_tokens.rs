@@ -0,0 +1,64 @@
+use near_sdk::json_types::U128;
+
+use crate::*;
+use crate::tests::test_emulator::*;
+
+#[test]
+fn test_unsold_tokens_are_recorded_at_distribution() {
+    let (emulator, project_id) = init_distributed_project();
+
+    let project = emulator.contract.get_project(project_id).unwrap();
+    assert_eq!(ProjectStatus::Distribution, project.status);
+    assert_eq!(U128(40), project.unsold_token_amount);
+    assert_eq!(None, project.unsold_token_receiver_id);
+}
+
+#[test]
+fn test_withdraw_unsold_tokens() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("token.near".to_string(), "token.near".to_string(), 1);
+
+    emulator.contract.withdraw_unsold_tokens(project_id, false);
+
+    assert_eq!(Some("token.near".to_string()), emulator.contract.get_project(project_id).unwrap().unsold_token_receiver_id);
+}
+
+#[test]
+fn test_burn_unsold_tokens() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("alice".to_string(), "owner".to_string(), 0);
+    emulator.contract.set_burn_account_id(Some("burn.near".to_string()));
+    emulator.set_account_id_and_desposit("token.near".to_string(), "token.near".to_string(), 1);
+
+    emulator.contract.withdraw_unsold_tokens(project_id, true);
+
+    assert_eq!(Some("burn.near".to_string()), emulator.contract.get_project(project_id).unwrap().unsold_token_receiver_id);
+}
+
+#[test]
+#[should_panic(expected = "The burn account isn't configured.")]
+fn test_burn_unsold_tokens_without_burn_account() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("token.near".to_string(), "token.near".to_string(), 1);
+
+    emulator.contract.withdraw_unsold_tokens(project_id, true);
+}
+
+#[test]
+#[should_panic(expected = "The unsold tokens are already withdrawn.")]
+fn test_withdraw_unsold_tokens_twice() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("token.near".to_string(), "token.near".to_string(), 1);
+
+    emulator.contract.withdraw_unsold_tokens(project_id, false);
+    emulator.contract.withdraw_unsold_tokens(project_id, false);
+}
+
+#[test]
+#[should_panic(expected = "You are not the owner of this project.")]
+fn test_withdraw_unsold_tokens_by_non_owner() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+
+    emulator.contract.withdraw_unsold_tokens(project_id, false);
+}
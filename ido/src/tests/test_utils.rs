@@ -170,6 +170,11 @@ impl VMContextBuilder {
 }
 
 pub fn testing_env_with_promise_results(context: VMContext, promise_result: PromiseResult) {
+    testing_env_with_many_promise_results(context, vec![promise_result]);
+}
+
+/// The results of the joined promises in the order that they were joined.
+pub fn testing_env_with_many_promise_results(context: VMContext, promise_results: Vec<PromiseResult>) {
     let storage = near_sdk::env::take_blockchain_interface()
         .unwrap()
         .as_mut_mocked_blockchain()
@@ -180,7 +185,7 @@ pub fn testing_env_with_promise_results(context: VMContext, promise_result: Prom
         context,
         Default::default(),
         Default::default(),
-        vec![promise_result],
+        promise_results,
         storage,
         Default::default(),
         None
@@ -0,0 +1,153 @@
+use std::convert::TryFrom;
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::PromiseResult;
+
+use crate::*;
+use crate::tests::test_emulator::*;
+use crate::tests::test_utils::testing_env_with_promise_results;
+
+const VESTING_DURATION: Timestamp = 1000;
+
+fn new_vested_project_input() -> ProjectInput {
+    ProjectInput {
+        distribution_type: DistributionType::Vested,
+        ..new_project_input("token.near", "ft_contract", SaleType::Fcfs { max_allocation_per_user: 1000 })
+    }
+}
+
+/// A vested FCFS project in which bob buys 60 tokens, 25% of them are unlocked at the distribution.
+fn init_vested_project() -> (Emulator, ProjectId) {
+    let (mut emulator, project_id) = init_sales_project(new_vested_project_input(), &["bob"]);
+    emulator.contract.set_project_vesting_schedule(project_id, VestingSchedule {
+        unlocked_percentage: 25,
+        start_date: SALE_END_DATE,
+        end_date: SALE_END_DATE + VESTING_DURATION,
+    });
+    distribute_project(&mut emulator, project_id, &[("bob", 600)]);
+
+    (emulator, project_id)
+}
+
+fn mint_bob_vesting_token(emulator: &mut Emulator, project_id: ProjectId) -> VestingTokenId {
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 0);
+    emulator.contract.mint_vesting_token(project_id)
+}
+
+#[test]
+fn test_vested_distribution() {
+    let (emulator, project_id) = init_vested_project();
+
+    let distribution_data = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(U128(15), distribution_data.unlocked_amount);
+    assert_eq!(U128(45), distribution_data.locked_amount);
+}
+
+#[test]
+fn test_vested_project_without_schedule_is_distributed_unlocked() {
+    let (mut emulator, project_id) = init_sales_project(new_vested_project_input(), &["bob"]);
+    distribute_project(&mut emulator, project_id, &[("bob", 600)]);
+
+    let distribution_data = emulator.contract.get_project_account_info(project_id, "bob".to_string()).distribution_data.unwrap();
+    assert_eq!(U128(60), distribution_data.unlocked_amount);
+    assert_eq!(U128(0), distribution_data.locked_amount);
+}
+
+#[test]
+fn test_locked_amount_is_released_over_time() {
+    let (mut emulator, project_id) = init_vested_project();
+    let bob = "bob".to_string();
+    assert_eq!(vec![(project_id, U128(15))], emulator.contract.internal_claim_rounds(project_id, &bob));
+
+    emulator.set_block_timestamp(SALE_END_DATE + VESTING_DURATION / 2);
+    assert_eq!(vec![(project_id, U128(37))], emulator.contract.internal_claim_rounds(project_id, &bob));
+
+    emulator.set_block_timestamp(SALE_END_DATE + VESTING_DURATION);
+    assert_eq!(vec![(project_id, U128(60))], emulator.contract.internal_claim_rounds(project_id, &bob));
+}
+
+#[test]
+fn test_mint_vesting_token() {
+    let (mut emulator, project_id) = init_vested_project();
+    let token_id = mint_bob_vesting_token(&mut emulator, project_id);
+
+    assert_eq!(format!("{}:bob", project_id), token_id);
+    // The original account can't claim the minted position anymore
+    assert!(emulator.contract.internal_claim_rounds(project_id, &"bob".to_string()).is_empty());
+
+    let token = emulator.contract.nft_token(token_id.clone()).unwrap();
+    assert_eq!("bob".to_string(), token.owner_id);
+    let vesting_state = emulator.contract.get_vesting_state(token_id);
+    assert_eq!(U128(15), vesting_state.claimable_amount);
+    assert_eq!(near_sdk::serde_json::to_string(&vesting_state).unwrap(), token.metadata.unwrap().extra.unwrap());
+}
+
+#[test]
+#[should_panic(expected = "The vesting token is already minted.")]
+fn test_mint_vesting_token_twice() {
+    let (mut emulator, project_id) = init_vested_project();
+    mint_bob_vesting_token(&mut emulator, project_id);
+    mint_bob_vesting_token(&mut emulator, project_id);
+}
+
+#[test]
+#[should_panic(expected = "The project isn't vested.")]
+fn test_mint_vesting_token_of_unlocked_project() {
+    let (mut emulator, project_id) = init_distributed_project();
+    mint_bob_vesting_token(&mut emulator, project_id);
+}
+
+#[test]
+fn test_holder_claims_the_vesting_token() {
+    let (mut emulator, project_id) = init_vested_project();
+    let token_id = mint_bob_vesting_token(&mut emulator, project_id);
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.nft_transfer(ValidAccountId::try_from("carol").unwrap(), token_id.clone(), None, None);
+    assert_eq!("carol".to_string(), emulator.contract.nft_token(token_id.clone()).unwrap().owner_id);
+
+    emulator.set_block_timestamp(SALE_END_DATE + VESTING_DURATION / 2);
+    emulator.set_account_id_and_desposit("carol".to_string(), "carol".to_string(), 1);
+    emulator.contract.claim_vesting_token(token_id.clone());
+
+    let vesting_state = emulator.contract.get_vesting_state(token_id);
+    assert_eq!(U128(37), vesting_state.claimed_amount);
+    assert_eq!(U128(0), vesting_state.claimable_amount);
+}
+
+#[test]
+#[should_panic(expected = "The caller doesn't own the vesting token.")]
+fn test_original_account_claims_the_transferred_vesting_token() {
+    let (mut emulator, project_id) = init_vested_project();
+    let token_id = mint_bob_vesting_token(&mut emulator, project_id);
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.nft_transfer(ValidAccountId::try_from("carol").unwrap(), token_id.clone(), None, None);
+
+    emulator.contract.claim_vesting_token(token_id);
+}
+
+#[test]
+fn test_nft_resolve_transfer_returns_the_token() {
+    let (mut emulator, project_id) = init_vested_project();
+    let token_id = mint_bob_vesting_token(&mut emulator, project_id);
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+    emulator.contract.nft_transfer_call(ValidAccountId::try_from("market").unwrap(), token_id.clone(), None, None, String::new());
+
+    let mut context = emulator.context.clone();
+    context.predecessor_account_id = context.current_account_id.clone();
+    testing_env_with_promise_results(context, PromiseResult::Successful(b"true".to_vec()));
+
+    assert!(!emulator.contract.nft_resolve_transfer("bob".to_string(), "market".to_string(), token_id.clone(), None));
+    assert_eq!("bob".to_string(), emulator.contract.nft_token(token_id).unwrap().owner_id);
+}
+
+#[test]
+#[should_panic(expected = "The project isn't vested.")]
+fn test_set_vesting_schedule_of_unlocked_project() {
+    let project_input = new_project_input("token.near", "ft_contract", SaleType::Fcfs { max_allocation_per_user: 1000 });
+    let (mut emulator, project_id) = init_sales_project(project_input, &["bob"]);
+
+    emulator.contract.set_project_vesting_schedule(project_id, VestingSchedule {
+        unlocked_percentage: 25,
+        start_date: SALE_END_DATE,
+        end_date: SALE_END_DATE + VESTING_DURATION,
+    });
+}
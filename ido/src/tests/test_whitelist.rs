@@ -7,12 +7,13 @@ fn test_register_whitelist() {
     let mut emulator = Emulator::default();
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
 
-    emulator.contract.create_sample_projects();
+    emulator.create_sample_projects();
     emulator.contract.internal_change_project_status(1);
     emulator.contract.internal_change_project_status(2);
     emulator.update_context("alice".to_string(), "bob".to_string(), 0);
-    emulator.contract.register_whitelist(1);
-    emulator.contract.register_whitelist(2);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.register_whitelist(1, None);
+    emulator.contract.register_whitelist(2, None);
     assert_eq!(emulator.contract.projects_by_account.get(&"bob".to_string()).unwrap().len(),2);
 }
 
@@ -21,11 +22,12 @@ fn test_is_whitelist(){
     let mut emulator = Emulator::default();
     emulator.update_context("alice".to_string(), "owner".to_string(), 0);
 
-    emulator.contract.create_sample_projects();
+    emulator.create_sample_projects();
     emulator.contract.internal_change_project_status(1);
     emulator.contract.internal_change_project_status(2);
     emulator.update_context("alice".to_string(), "bob".to_string(), 0);
-    emulator.contract.register_whitelist(1);
+    emulator.register_storage("bob".to_string());
+    emulator.contract.register_whitelist(1, None);
     assert!(emulator.contract.is_whitelist(1,"bob".to_string()));
     assert!(!emulator.contract.is_whitelist(1,"alice".to_string()));
 }
\ No newline at end of file
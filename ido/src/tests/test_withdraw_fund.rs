@@ -0,0 +1,67 @@
+use near_sdk::json_types::U128;
+use near_sdk::PromiseResult;
+
+use crate::*;
+use crate::tests::test_emulator::*;
+use crate::tests::test_utils::testing_env_with_promise_results;
+
+fn resolve_withdraw_fund(emulator: &mut Emulator, project_id: ProjectId, withdraw_amount: Balance, promise_result: PromiseResult) -> U128 {
+    let mut context = emulator.context.clone();
+    context.predecessor_account_id = context.current_account_id.clone();
+    testing_env_with_promise_results(context, promise_result);
+
+    emulator.contract.withdraw_fund_callback(project_id, U128(withdraw_amount))
+}
+
+#[test]
+fn test_withdraw_fund() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("token.near".to_string(), "token.near".to_string(), 1);
+
+    emulator.contract.withdraw_fund(project_id);
+
+    assert_eq!(U128(600), emulator.contract.get_project(project_id).unwrap().withdrawn_fund_amount);
+    assert_eq!(U128(600), resolve_withdraw_fund(&mut emulator, project_id, 600, PromiseResult::Successful(vec![])));
+}
+
+#[test]
+fn test_withdraw_fund_is_restored_when_the_transfer_fails() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("token.near".to_string(), "token.near".to_string(), 1);
+    emulator.contract.withdraw_fund(project_id);
+
+    assert_eq!(U128(0), resolve_withdraw_fund(&mut emulator, project_id, 600, PromiseResult::Failed));
+    assert_eq!(U128(0), emulator.contract.get_project(project_id).unwrap().withdrawn_fund_amount);
+}
+
+#[test]
+fn test_withdraw_fund_keeps_the_refunds() {
+    let project_input = new_project_input("token.near", "", SaleType::Overflow);
+    let (mut emulator, project_id) = init_sales_project(project_input, &["bob", "carol"]);
+    distribute_project(&mut emulator, project_id, &[("bob", 1500), ("carol", 500)]);
+    emulator.set_account_id_and_desposit("token.near".to_string(), "token.near".to_string(), 1);
+
+    emulator.contract.withdraw_fund(project_id);
+
+    // Only the fund of the 100 sold tokens is withdrawn, the oversubscribed fund is refundable
+    assert_eq!(U128(1000), emulator.contract.get_project(project_id).unwrap().withdrawn_fund_amount);
+}
+
+#[test]
+#[should_panic(expected = "The project has no fund to withdraw.")]
+fn test_withdraw_fund_twice() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("token.near".to_string(), "token.near".to_string(), 1);
+
+    emulator.contract.withdraw_fund(project_id);
+    emulator.contract.withdraw_fund(project_id);
+}
+
+#[test]
+#[should_panic(expected = "You are not the owner of this project.")]
+fn test_withdraw_fund_by_non_owner() {
+    let (mut emulator, project_id) = init_distributed_project();
+    emulator.set_account_id_and_desposit("bob".to_string(), "bob".to_string(), 1);
+
+    emulator.contract.withdraw_fund(project_id);
+}
@@ -3,6 +3,7 @@ use crate::*;
 pub(crate) const ERROR_1: &str = "";
 
 pub(crate) const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+pub(crate) const ONE_DAY_IN_NANOSECONDS: u64 = 86_400_000_000_000;
 
 pub(crate) fn get_storage_key(key: StorageKey) -> Vec<u8> {
     key.try_to_vec().unwrap()
@@ -20,6 +21,41 @@ pub(crate) fn get_token_raised_human_amount(amount: u128, token_decimal: u8) ->
     amount / u128::pow(10, token_decimal as u32) 
 }
 
+/// a * b / c without overflowing on the product, ex: a NEAR amount of 24 decimals times a token amount of 18 decimals.
+pub(crate) fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    assert_ne!(c, 0, "Division by zero.");
+    if let Some(product) = a.checked_mul(b) {
+        return product / c;
+    }
+
+    // The 256-bit product as the high and the low 128 bits
+    let half_mask = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & half_mask);
+    let (b_high, b_low) = (b >> 64, b & half_mask);
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let middle = (low_low >> 64) + (high_low & half_mask) + (low_high & half_mask);
+    let low = (low_low & half_mask) | (middle << 64);
+    let high = a_high * b_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+
+    // The long division of the product by c, one bit at a time
+    let mut quotient: u128 = 0;
+    let mut remainder: u128 = 0;
+    for bit_index in (0..256).rev() {
+        let bit = if bit_index >= 128 { (high >> (bit_index - 128)) & 1 } else { (low >> bit_index) & 1 };
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | bit;
+        if carry == 1 || remainder >= c {
+            assert!(bit_index < 128, "The result overflows u128.");
+            remainder = remainder.wrapping_sub(c);
+            quotient |= 1 << bit_index;
+        }
+    }
+
+    quotient
+}
+
 pub(crate) fn hash_account_id(account_id: &AccountId) -> CryptoHash{
     let mut hash = CryptoHash::default();
     // Hash account ID rồi return chính nó
@@ -28,8 +64,29 @@ pub(crate) fn hash_account_id(account_id: &AccountId) -> CryptoHash{
     hash
 }
 
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The block timestamp plus the time offset which is only set in test mode.
+/// The offset is cleared when the contract is initialized or migrated without test mode, so it never applies outside test mode.
 pub(crate) fn get_current_time() -> Timestamp {
-    env::block_timestamp()
+    env::block_timestamp() + read_time_offset()
+}
+
+/// The time offset is stored under its own key so that get_current_time doesn't need the contract state.
+pub(crate) fn read_time_offset() -> Timestamp {
+    env::storage_read(&get_storage_key(StorageKey::TimeOffsetKey))
+        .map(|value| Timestamp::try_from_slice(&value).unwrap())
+        .unwrap_or(0)
+}
+
+pub(crate) fn write_time_offset(time_offset: Timestamp) {
+    env::storage_write(&get_storage_key(StorageKey::TimeOffsetKey), &time_offset.try_to_vec().unwrap());
+}
+
+pub(crate) fn clear_time_offset() {
+    env::storage_remove(&get_storage_key(StorageKey::TimeOffsetKey));
 }
 
 pub(crate) fn assert_one_yocto() {
@@ -61,6 +118,21 @@ impl IDOContract{
         self.assert_test_mode();
         self.assert_owner();
     }
+
+    pub(crate) fn assert_admin(&self) {
+        let account_id = env::signer_account_id();
+        assert!(self.owner_id == account_id || self.admin_ids.contains(&account_id), "You are not an admin of this contract.");
+    }
+
+    /// The project is paused by itself or by the whole contract.
+    pub(crate) fn is_project_paused(&self, project: &Project) -> bool {
+        self.paused || project.paused
+    }
+
+    pub(crate) fn assert_not_paused(&self, project: &Project) {
+        assert!(!self.paused, "The contract is paused.");
+        assert!(!project.paused, "The project is paused.");
+    }
 }
 
 #[near_bindgen]
@@ -88,6 +88,15 @@ pub fn init() -> (UserAccount, UserAccount, UserAccount,UserAccount, UserAccount
         to_yocto("0.1")
     );
 
+    // Alice pays for her storage on IDO contract
+    alice.call(
+        ido_contract.account_id(),
+        "storage_deposit",
+        &json!({}).to_string().as_bytes(),
+        DEFAULT_GAS,
+        to_yocto("0.1")
+    );
+
     // Transfer 50% total supply to staking contract
     alice.call(
         ft_contract.account_id(), 
@@ -29,7 +29,16 @@ impl FungibleTokenReceiver for StakingContract {
      * 2. handle stake
      */
     fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
-        let upgradable_account: Option<UpgradableAccount> = self.accounts.get(&sender_id);
+        // A trusted staker can stake and lock for another account with the message "lock:{locked_days}:{account_id}"
+        let args: Vec<&str> = msg.split(":").collect();
+        let account_id: AccountId = if args[0] == "lock" && args.len() >= 3 {
+            assert!(Self::trusted_stakers().contains(&sender_id), "ERR_NOT_TRUSTED_STAKER");
+            args[2].trim().to_string()
+        } else {
+            sender_id.clone()
+        };
+
+        let upgradable_account: Option<UpgradableAccount> = self.accounts.get(&account_id);
         assert!(upgradable_account.is_some(), "ERR_NOT_FOUND_ACCOUNT");
         assert!(!self.paused, "ERR_CONTRACT_PAUSED");
         assert_eq!(self.ft_contract_id, env::predecessor_account_id(), "ERR_NOT_VALID_FT_CONTRACT");
@@ -38,16 +47,15 @@ impl FungibleTokenReceiver for StakingContract {
         println!("ft_on_transfer(sender_id: {}, amount: {}, msg: {})", sender_id, amount.0, msg);
 
         // Staking
-        self.internal_deposit_and_stake(sender_id.clone(), amount.0);
+        self.internal_deposit_and_stake(account_id.clone(), amount.0);
 
         // Locking
-        let args: Vec<&str> = msg.split(":").collect();
         if args.len() >= 1 {
             match args[0] {
                 "lock" => {
                     let locked_days: DayType = args[1].trim().parse().unwrap();
-                    self.internal_lock(sender_id.clone(), amount.0, locked_days);
-                    env::log(format!("Lock amount of {} KULA for account {} in {} day(s).", amount.0, sender_id, locked_days).as_bytes());
+                    self.internal_lock(account_id.clone(), amount.0, locked_days);
+                    env::log(format!("Lock amount of {} KULA for account {} in {} day(s).", amount.0, account_id, locked_days).as_bytes());
                 },
                 _ => {}
             }
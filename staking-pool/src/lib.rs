@@ -5,7 +5,7 @@ mod modules;
 mod util;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LookupMap};
+use near_sdk::collections::{LookupMap, UnorderedSet};
 use near_sdk::json_types::{U128};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
@@ -83,6 +83,7 @@ impl Default for Config {
 #[derive(BorshDeserialize, BorshSerialize, BorshStorageKey)]
 pub enum StorageKey {
     AccountKey,
+    TrustedStakerKey,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
@@ -181,6 +182,26 @@ impl StakingContract {
         self.assert_owner();
         self.internal_unlock(account_id);
     }
+
+    /// The contracts that can stake and lock tokens for other accounts. Ex: the IDO contract claims and stakes for its users.
+    /// They're stored under their own storage key so that the contract state doesn't need a migration.
+    pub(crate) fn trusted_stakers() -> UnorderedSet<AccountId> {
+        UnorderedSet::new(StorageKey::TrustedStakerKey)
+    }
+
+    pub fn add_trusted_staker(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        Self::trusted_stakers().insert(&account_id);
+    }
+
+    pub fn remove_trusted_staker(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        Self::trusted_stakers().remove(&account_id);
+    }
+
+    pub fn get_trusted_stakers(&self) -> Vec<AccountId> {
+        Self::trusted_stakers().to_vec()
+    }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
@@ -189,6 +210,7 @@ mod tests {
     use near_sdk::json_types::ValidAccountId;
     use near_sdk::test_utils::{accounts, VMContextBuilder};
     use near_sdk::{testing_env, MockedBlockchain};
+    use crate::core_impl::FungibleTokenReceiver;
 
     fn get_context(is_view: bool) -> VMContextBuilder {
         let mut builder = VMContextBuilder::new();
@@ -391,6 +413,44 @@ mod tests {
         assert_eq!(account.unstake_available_epoch_height, 11);
     }
 
+    #[test]
+    fn stake_and_lock_by_trusted_staker_test() {
+        let mut context = get_context(false);
+        testing_env!(context.build());
+
+        let mut contract: StakingContract =
+            StakingContract::new_default_config(accounts(1).to_string(), accounts(1).to_string());
+        contract.internal_create_account(accounts(0).to_string());
+
+        // The owner trusts accounts(2), then accounts(2) transfers the token for accounts(0) through the FT contract
+        context.predecessor_account_id(accounts(1));
+        testing_env!(context.build());
+        contract.add_trusted_staker(accounts(2).to_string());
+        assert_eq!(vec![accounts(2).to_string()], contract.get_trusted_stakers());
+
+        contract.ft_on_transfer(accounts(2).to_string(), U128(10_000_000_000_000), format!("lock:30:{}", accounts(0)));
+
+        let account: Account = Account::from(contract.accounts.get(&accounts(0).to_string()).unwrap());
+        assert_eq!(account.staked_balance, 10_000_000_000_000);
+        assert_eq!(account.locked_balance, 10_000_000_000_000);
+        assert_eq!(account.locked_days, 30);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NOT_TRUSTED_STAKER")]
+    fn stake_and_lock_by_untrusted_staker_test() {
+        let mut context = get_context(false);
+        testing_env!(context.build());
+
+        let mut contract: StakingContract =
+            StakingContract::new_default_config(accounts(1).to_string(), accounts(1).to_string());
+        contract.internal_create_account(accounts(0).to_string());
+
+        context.predecessor_account_id(accounts(1));
+        testing_env!(context.build());
+        contract.ft_on_transfer(accounts(2).to_string(), U128(10_000_000_000_000), format!("lock:30:{}", accounts(0)));
+    }
+
     #[test]
     fn withdraw_test() {}
 
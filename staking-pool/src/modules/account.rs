@@ -45,6 +45,7 @@ pub struct AccountJson {
     pub account_id: AccountId,
     pub locked_balance: U128,
     pub locked_days: DayType,
+    pub locked_timestamp: Timestamp,
     pub unlocked_timestamp: Timestamp,
     pub staked_balance: U128,
     pub unstaked_balance: U128,